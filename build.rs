@@ -0,0 +1,337 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use git2::{DiffOptions, Repository, Sort};
+use image::codecs::ico::{IcoEncoder, IcoFrame};
+use image::imageops::{self, FilterType};
+use image::{ExtendedColorType, Rgba, RgbaImage};
+use sha2::{Digest, Sha256, Sha384};
+use time::{OffsetDateTime, UtcOffset};
+
+#[path = "src/site.rs"]
+#[allow(dead_code)]
+mod site;
+#[path = "src/svg.rs"]
+mod svg;
+
+// Anything changing under a watched path reruns the whole build script, so the slow steps are skipped when their own inputs ('key') haven't changed since they last ran.
+// This file is part of the key too, as the outputs of the previous build script stick around.
+
+fn cached(out_dir: &Path, step: &str, key: &[u8], run: impl FnOnce()) {
+	let stamp = out_dir.join(format!("{}.stamp", step));
+	let hash = Sha256::new()
+		.chain_update(include_str!("build.rs"))
+		.chain_update(key)
+		.finalize()
+		.to_vec();
+
+	if fs::read(&stamp).is_ok_and(|old| old == hash) {
+		return;
+	}
+
+	run();
+	fs::write(stamp, hash).unwrap();
+}
+
+// Icons are minified and turned into '<symbol>'s at compile time, so that 'base()' can emit a single sprite per page which all the icons on that page then '<use>'.
+
+fn icons(out_dir: &Path) {
+	let icons_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("public/icons");
+	println!("cargo:rerun-if-changed={}", icons_dir.display());
+
+	let mut paths: Vec<_> = fs::read_dir(&icons_dir)
+		.unwrap()
+		.map(|entry| entry.unwrap().path())
+		.filter(|path| path.extension().is_some_and(|ext| ext == "svg"))
+		.collect();
+
+	paths.sort();
+
+	let mut icons = String::new();
+
+	for path in paths {
+		let name = path.file_stem().unwrap().to_str().unwrap();
+		let (view_box, symbol) = svg::minify(&fs::read_to_string(&path).unwrap());
+
+		icons.push_str(&format!("\t({:?}, {:?}, {:?}),\n", name, view_box, symbol));
+	}
+
+	let src = format!(
+		"// (name, viewBox, minified contents)\npub const ICONS: &[(&str, &str, &str)] = &[\n{}];\n",
+		icons
+	);
+	fs::write(out_dir.join("icons.rs"), src).unwrap();
+}
+
+// The favicon, PWA icons, Apple touch icon, and Apple startup images are all generated from 'site::ICON'.
+
+fn colour(hex: &str) -> Rgba<u8> {
+	let hex = hex.trim_start_matches('#');
+	let channel = |i: usize| u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).unwrap();
+
+	Rgba([channel(0), channel(1), channel(2), 0xFF])
+}
+
+// Icon scaled to 'icon_size' and centered on a 'width'x'height' opaque background.
+
+fn flatten(icon: &RgbaImage, width: u32, height: u32, icon_size: u32) -> RgbaImage {
+	let mut flattened = RgbaImage::from_pixel(width, height, colour(site::BACKGROUND_COLOUR));
+	let icon = imageops::resize(icon, icon_size, icon_size, FilterType::Lanczos3);
+
+	let x = (width - icon_size) / 2;
+	let y = (height - icon_size) / 2;
+
+	imageops::overlay(&mut flattened, &icon, x.into(), y.into());
+	flattened
+}
+
+fn pwa(out_dir: &Path) {
+	let icon_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(format!("public{}", site::ICON));
+	let site_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/site.rs");
+	println!("cargo:rerun-if-changed={}", icon_path.display());
+	println!("cargo:rerun-if-changed={}", site_path.display());
+
+	let key = [fs::read(&icon_path).unwrap(), fs::read(&site_path).unwrap()].concat();
+	cached(out_dir, "pwa", &key, || generate_pwa(out_dir, &icon_path));
+}
+
+fn generate_pwa(out_dir: &Path, icon_path: &Path) {
+	let icon = image::open(icon_path).unwrap().to_rgba8();
+	let pwa_dir = out_dir.join("pwa");
+	fs::create_dir_all(&pwa_dir).unwrap();
+
+	// (route, content type, file name in 'pwa_dir')
+
+	let mut files: Vec<(String, &str, String)> = Vec::new();
+
+	let mut save = |route: String, image: RgbaImage| {
+		let name = route.trim_start_matches('/').replace('/', "-");
+		image.save(pwa_dir.join(&name)).unwrap();
+		files.push((route, "image/png", name));
+	};
+
+	for &size in site::ICON_SIZES {
+		save(
+			format!("/pwa/icon-{}.png", size),
+			imageops::resize(&icon, size, size, FilterType::Lanczos3),
+		);
+
+		// Maskable icons may be cropped to a circle with a diameter of 80% of the icon's size, so we need some padding.
+
+		save(
+			format!("/pwa/maskable-{}.png", size),
+			flatten(&icon, size, size, size * 8 / 10),
+		);
+	}
+
+	// iOS fills in transparent areas of the touch icon with black, so flatten it ourselves.
+
+	let size = site::APPLE_TOUCH_ICON_SIZE;
+	save("/apple-touch-icon.png".to_string(), flatten(&icon, size, size, size));
+
+	for &(width, height, _) in site::STARTUP_IMAGES {
+		save(
+			format!("/pwa/startup-{}x{}.png", width, height),
+			flatten(&icon, width, height, width / 4),
+		);
+	}
+
+	// Favicon, with all the sizes packed into the one ICO.
+
+	let frames: Vec<IcoFrame> = site::FAVICON_SIZES
+		.iter()
+		.map(|&size| {
+			let resized = imageops::resize(&icon, size, size, FilterType::Lanczos3);
+			IcoFrame::as_png(resized.as_raw(), size, size, ExtendedColorType::Rgba8).unwrap()
+		})
+		.collect();
+
+	let ico = fs::File::create(pwa_dir.join("favicon.ico")).unwrap();
+	IcoEncoder::new(ico).encode_images(&frames).unwrap();
+	files.push(("/favicon.ico".to_string(), "image/x-icon", "favicon.ico".to_string()));
+
+	let files: String = files
+		.iter()
+		.map(|(route, content_type, name)| {
+			format!(
+				"\t({:?}, {:?}, include_bytes!({:?})),\n",
+				route,
+				content_type,
+				pwa_dir.join(name).display().to_string()
+			)
+		})
+		.collect();
+
+	let src = format!(
+		"// (route, content type, contents)\npub const FILES: &[(&str, &str, &[u8])] = &[\n{}];\n",
+		files
+	);
+	fs::write(out_dir.join("pwa.rs"), src).unwrap();
+}
+
+// All the files under 'dir', recursively and in a stable order.
+
+fn walk(dir: &Path) -> Vec<PathBuf> {
+	let mut paths: Vec<_> = fs::read_dir(dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+	paths.sort();
+
+	paths
+		.into_iter()
+		.flat_map(|path| if path.is_dir() { walk(&path) } else { vec![path] })
+		.collect()
+}
+
+// Subresource integrity hashes of the scripts and stylesheets in 'public/', for the 'asset!' macro.
+
+fn integrity(out_dir: &Path) {
+	let public_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("public");
+	println!("cargo:rerun-if-changed={}", public_dir.display());
+
+	let assets: String = walk(&public_dir)
+		.iter()
+		.filter(|path| path.extension().is_some_and(|ext| ext == "js" || ext == "css"))
+		.map(|path| {
+			let route = Path::new("/").join(path.strip_prefix(&public_dir).unwrap());
+			let hash = BASE64_STANDARD.encode(Sha384::digest(fs::read(path).unwrap()));
+
+			format!("\t({:?}, \"sha384-{}\"),\n", route.display().to_string(), hash)
+		})
+		.collect();
+
+	let src = format!(
+		"// (path relative to 'public/', integrity)\npub const INTEGRITY: &[(&str, &str)] = &[\n{}];\n",
+		assets
+	);
+	fs::write(out_dir.join("integrity.rs"), src).unwrap();
+}
+
+// Git history of the posts in 'public/blog/', newest first, for their "last updated" dates and '/<post>/history' pages.
+// Shallow clones (like CI's) don't get any, as the oldest commit they have would otherwise look like it added every post.
+
+fn revisions(repo: &Repository) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+	if repo.is_shallow() {
+		return Err("it's a shallow clone, so it's missing some of it".into());
+	}
+
+	let mut revisions: BTreeMap<String, String> = BTreeMap::new();
+
+	let workdir = repo.workdir().ok_or("it's a bare repo")?.canonicalize()?;
+	let public_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("public").canonicalize()?;
+	let blog_dir = public_dir.join("blog");
+
+	let mut revwalk = repo.revwalk()?;
+	revwalk.set_sorting(Sort::TIME)?;
+	revwalk.push_head()?;
+
+	for oid in revwalk {
+		let commit = repo.find_commit(oid?)?;
+
+		// Merges don't change anything by themselves.
+
+		if commit.parent_count() > 1 {
+			continue;
+		}
+
+		let tree = commit.tree()?;
+		let parent_tree = commit.parents().next().map(|parent| parent.tree()).transpose()?;
+
+		let mut options = DiffOptions::new();
+		options.pathspec(blog_dir.strip_prefix(&workdir)?);
+
+		let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut options))?;
+
+		let time = commit.time();
+		let offset = UtcOffset::from_whole_seconds(time.offset_minutes() * 60)?;
+		let date = OffsetDateTime::from_unix_timestamp(time.seconds())?
+			.to_offset(offset)
+			.date();
+
+		let revision = format!(
+			"\t\tRevision {{ hash: {:?}, date: \"{}/{}/{}\", summary: {:?} }},\n",
+			commit.id().to_string(),
+			date.day(),
+			date.month() as u8,
+			date.year(),
+			commit.summary().unwrap_or_default()
+		);
+
+		for delta in diff.deltas() {
+			if let Some(path) = delta
+				.new_file()
+				.path()
+				.filter(|path| path.extension().is_some_and(|ext| ext == "md"))
+			{
+				let route = Path::new("/").join(workdir.join(path).strip_prefix(&public_dir)?);
+				revisions
+					.entry(route.display().to_string())
+					.or_default()
+					.push_str(&revision);
+			}
+		}
+	}
+
+	Ok(revisions)
+}
+
+// Builds from outside a git repo (or from one without any commits yet) still work, they just don't get any history.
+
+fn history(out_dir: &Path) {
+	let repo = match Repository::discover(env!("CARGO_MANIFEST_DIR")) {
+		Ok(repo) => repo,
+		Err(_) => {
+			println!("cargo:warning=not building from a git repo, so posts won't have any history");
+			write_history(out_dir, BTreeMap::new());
+			return;
+		}
+	};
+
+	// Committing doesn't touch any of the files we're otherwise watching.
+
+	for path in ["HEAD", "refs", "packed-refs", "shallow"].map(|path| repo.path().join(path)) {
+		if path.exists() {
+			println!("cargo:rerun-if-changed={}", path.display());
+		}
+	}
+
+	// The history only changes with new commits, so there's no need to walk it again otherwise.
+
+	let head = repo.head().ok().and_then(|head| head.target());
+	let key = format!("{:?} {}", head, repo.is_shallow());
+
+	cached(out_dir, "history", key.as_bytes(), || {
+		let revisions = revisions(&repo).unwrap_or_else(|err| {
+			println!(
+				"cargo:warning=couldn't read the git history, so posts won't have any: {}",
+				err
+			);
+			BTreeMap::new()
+		});
+
+		write_history(out_dir, revisions);
+	});
+}
+
+fn write_history(out_dir: &Path, revisions: BTreeMap<String, String>) {
+	let entries: String = revisions
+		.iter()
+		.map(|(route, revisions)| format!("\t({:?}, &[\n{}\t]),\n", route, revisions))
+		.collect();
+
+	let src = format!(
+		"// (path relative to 'public/', revisions)\npub const HISTORY: &[(&str, &[Revision])] = &[\n{}];\n",
+		entries
+	);
+	fs::write(out_dir.join("history.rs"), src).unwrap();
+}
+
+fn main() {
+	let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").unwrap());
+
+	icons(&out_dir);
+	pwa(&out_dir);
+	integrity(&out_dir);
+	history(&out_dir);
+}
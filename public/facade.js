@@ -0,0 +1,9 @@
+// swap click-to-load facades for the external content they stand in for
+// this may run more than once on a page, but replacing an already detached facade is a no-op
+
+for (const button of document.querySelectorAll(".facade > button")) {
+	button.addEventListener("click", () => {
+		const facade = button.parentElement
+		facade.replaceWith(facade.querySelector("template").content)
+	}, { once: true })
+}
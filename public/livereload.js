@@ -0,0 +1,3 @@
+// reload the page whenever the dev server tells us something changed (only included with the 'dev' feature)
+
+new EventSource("/__livereload").addEventListener("message", () => location.reload())
@@ -395,6 +395,8 @@ class Paturage {
 			return
 		}
 
+		canvas.addEventListener("click", () => this.click())
+
 		this.x_res = this.gl.drawingBufferWidth
 		this.y_res = this.gl.drawingBufferHeight
 
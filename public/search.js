@@ -0,0 +1,105 @@
+// search the static export, which has no server to render results for us
+// there's no stemmer here, so query words match terms they start with instead, which is close enough
+
+const MAX_RESULTS = 10
+const TYPO_WEIGHT = 0.5
+
+function levenshtein(a, b) {
+	let row = Array.from({ length: b.length + 1 }, (_, i) => i)
+
+	for (let i = 1; i <= a.length; i++) {
+		let prev = row[0]
+		row[0] = i
+
+		for (let j = 1; j <= b.length; j++) {
+			const tmp = row[j]
+			row[j] = Math.min(row[j] + 1, row[j - 1] + 1, prev + (a[i - 1] === b[j - 1] ? 0 : 1))
+			prev = tmp
+		}
+	}
+
+	return row[b.length]
+}
+
+function matches(terms, word) {
+	if (Object.hasOwn(terms, word)) {
+		return [[word, 1]]
+	}
+
+	const prefixes = Object.keys(terms).filter(term => term.length >= 4 && word.startsWith(term) && word.length - term.length <= 3)
+
+	if (prefixes.length) {
+		return prefixes.map(term => [term, 1])
+	}
+
+	if (word.length <= 3) {
+		return []
+	}
+
+	const tolerance = word.length <= 7 ? 1 : 2
+	return Object.keys(terms).filter(term => levenshtein(word, term) <= tolerance).map(term => [term, TYPO_WEIGHT])
+}
+
+async function search(query) {
+	const index = await (await fetch("/search.json")).json()
+	const scores = new Map()
+
+	for (const word of query.toLowerCase().split(/[^\p{L}\p{N}]+/u).filter(word => word)) {
+		for (const [term, confidence] of matches(index.terms, word)) {
+			const postings = index.terms[term]
+
+			for (let i = 0; i < postings.length; i += 2) {
+				scores.set(postings[i], (scores.get(postings[i]) || 0) + postings[i + 1] * confidence)
+			}
+		}
+	}
+
+	return [...scores.entries()]
+		.sort((a, b) => b[1] - a[1])
+		.slice(0, MAX_RESULTS)
+		.map(([i]) => index.documents[i])
+}
+
+async function render(query) {
+	const section = document.createElement("section")
+	section.id = "search-results"
+	section.setAttribute("aria-live", "polite")
+	document.querySelector("form.search").after(section)
+
+	const results = await search(query)
+
+	if (!results.length) {
+		const p = document.createElement("p")
+		p.textContent = `Nothing matches "${query}".`
+		section.append(p)
+		return
+	}
+
+	const ol = document.createElement("ol")
+	ol.className = "search-results"
+
+	for (const [route, title, kind] of results) {
+		const a = document.createElement("a")
+		a.className = "link"
+		a.href = route
+		a.textContent = title
+
+		const small = document.createElement("small")
+		small.textContent = kind
+
+		const li = document.createElement("li")
+		li.append(a, " ", small)
+		ol.append(li)
+	}
+
+	section.append(ol)
+}
+
+const query = (new URLSearchParams(location.search).get("q") || "").trim()
+
+// results are already there if the server rendered them
+
+if (query && !document.getElementById("search-results")) {
+	document.getElementById("search-input").value = query
+	render(query)
+}
@@ -0,0 +1,5 @@
+// register the service worker, so that the site works offline
+
+if ("serviceWorker" in navigator) {
+	navigator.serviceWorker.register("/sw.js")
+}
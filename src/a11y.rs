@@ -0,0 +1,211 @@
+use std::collections::HashSet;
+
+use scraper::{ElementRef, Html, Selector};
+
+use crate::links::KNOWN_MISSING;
+
+// Accessibility lints over rendered pages.
+// These don't replace actually trying a page out with a screen reader, but catch the usual mistakes.
+
+fn select<'a>(document: &'a Html, selector: &str) -> Vec<ElementRef<'a>> {
+	document.select(&Selector::parse(selector).unwrap()).collect()
+}
+
+// Short description of an element, so that failures can be tracked down.
+
+fn describe(element: &ElementRef) -> String {
+	let mut description = element.value().name().to_string();
+
+	for attr in ["id", "class", "href", "src"] {
+		if let Some(value) = element.value().attr(attr) {
+			description.push_str(&format!(" {}=\"{}\"", attr, value));
+		}
+	}
+
+	format!("<{}>", description)
+}
+
+fn has_attr(element: &ElementRef, attr: &str) -> bool {
+	element.value().attr(attr).is_some_and(|value| !value.trim().is_empty())
+}
+
+fn has_text(element: &ElementRef) -> bool {
+	element.text().any(|text| !text.trim().is_empty())
+}
+
+// Whether an element has an accessible name, from its contents or otherwise.
+
+fn has_name(element: &ElementRef) -> bool {
+	let alt = Selector::parse("img[alt]").unwrap();
+
+	has_attr(element, "aria-label") ||
+		has_attr(element, "aria-labelledby") ||
+		has_text(element) ||
+		element.select(&alt).any(|img| has_attr(&img, "alt"))
+}
+
+fn lang(document: &Html, errors: &mut Vec<String>) {
+	if !select(document, "html").iter().all(|html| has_attr(html, "lang")) {
+		errors.push("<html> has no 'lang' attribute".to_string());
+	}
+}
+
+// Empty alt text is fine, it just means the image is decorative.
+
+fn images(document: &Html, errors: &mut Vec<String>) {
+	for img in select(document, "img:not([alt])") {
+		errors.push(format!(
+			"{} has no alt text (use alt=\"\" if it's decorative)",
+			describe(&img)
+		));
+	}
+
+	for svg in select(document, "svg:not([aria-hidden=true])") {
+		if svg.value().attr("role") != Some("img") {
+			errors.push(format!(
+				"{} should either be aria-hidden=\"true\" or have role=\"img\"",
+				describe(&svg)
+			));
+		}
+
+		let titled = svg
+			.children()
+			.filter_map(ElementRef::wrap)
+			.any(|child| child.value().name() == "title" && has_text(&child));
+
+		if !has_attr(&svg, "aria-label") && !titled {
+			errors.push(format!("{} has no title", describe(&svg)));
+		}
+	}
+}
+
+// One '<h1>' per page, and no skipping levels on the way down (going back up is fine).
+
+fn headings(document: &Html, errors: &mut Vec<String>) {
+	let headings = select(document, "h1, h2, h3, h4, h5, h6");
+	let h1s = headings.iter().filter(|heading| heading.value().name() == "h1").count();
+
+	if h1s != 1 {
+		errors.push(format!("page has {} <h1>'s, instead of one", h1s));
+	}
+
+	let mut prev = 0;
+
+	for heading in headings {
+		let level = heading.value().name()[1..].parse().unwrap();
+
+		if level > prev + 1 && prev != 0 {
+			errors.push(format!("{} skips a level after an <h{}>", describe(&heading), prev));
+		}
+
+		prev = level;
+	}
+}
+
+fn landmarks(document: &Html, errors: &mut Vec<String>) {
+	let mains = select(document, "main, [role=main]").len();
+
+	if mains != 1 {
+		errors.push(format!("page has {} main landmarks, instead of one", mains));
+	}
+}
+
+fn labels(document: &Html, errors: &mut Vec<String>) {
+	let controls = select(
+		document,
+		"input:not([type=hidden]):not([type=submit]):not([type=button]), select, textarea",
+	);
+
+	for control in controls {
+		let labelled_for = control
+			.value()
+			.id()
+			.is_some_and(|id| !select(document, &format!("label[for=\"{}\"]", id)).is_empty());
+
+		let wrapped = control
+			.ancestors()
+			.filter_map(ElementRef::wrap)
+			.any(|ancestor| ancestor.value().name() == "label");
+
+		if !labelled_for && !wrapped && !has_attr(&control, "aria-label") && !has_attr(&control, "aria-labelledby") {
+			errors.push(format!("{} has no associated label", describe(&control)));
+		}
+	}
+}
+
+// Embedded content has no way of describing itself, so it needs a hand.
+
+fn embeds(document: &Html, errors: &mut Vec<String>) {
+	for iframe in select(document, "iframe") {
+		if !has_attr(&iframe, "title") {
+			errors.push(format!("{} has no title", describe(&iframe)));
+		}
+	}
+
+	for canvas in select(document, "canvas") {
+		if !has_text(&canvas) && !has_attr(&canvas, "aria-label") {
+			errors.push(format!("{} has no fallback content", describe(&canvas)));
+		}
+	}
+
+	// Videos which aren't in the repo yet can't be captioned yet either, but that stops as soon as they're taken off 'KNOWN_MISSING'.
+
+	let captions = Selector::parse("track[kind=captions], track[kind=subtitles]").unwrap();
+	let sources = Selector::parse("source[src]").unwrap();
+
+	for video in select(document, "video") {
+		let srcs: Vec<&str> = video
+			.value()
+			.attr("src")
+			.into_iter()
+			.chain(video.select(&sources).filter_map(|source| source.value().attr("src")))
+			.collect();
+
+		let missing = !srcs.is_empty() &&
+			srcs.iter()
+				.all(|src| KNOWN_MISSING.contains(&src.split('#').next().unwrap()));
+
+		if !missing && video.select(&captions).next().is_none() {
+			errors.push(format!("{} has no captions", describe(&video)));
+		}
+	}
+}
+
+// Icons count, as their titles are text.
+
+fn names(document: &Html, errors: &mut Vec<String>) {
+	for element in select(document, "a[href], button") {
+		if !has_name(&element) {
+			errors.push(format!("{} has no accessible name", describe(&element)));
+		}
+	}
+}
+
+// Duplicate IDs break 'label[for]', 'aria-labelledby', and anchors.
+
+fn ids(document: &Html, errors: &mut Vec<String>) {
+	let mut seen = HashSet::new();
+
+	for element in select(document, "[id]") {
+		let id = element.value().id().unwrap();
+
+		if !seen.insert(id) {
+			errors.push(format!("ID \"{}\" is used more than once", id));
+		}
+	}
+}
+
+pub fn lint(document: &Html) -> Vec<String> {
+	let mut errors = Vec::new();
+
+	lang(document, &mut errors);
+	images(document, &mut errors);
+	headings(document, &mut errors);
+	landmarks(document, &mut errors);
+	labels(document, &mut errors);
+	embeds(document, &mut errors);
+	names(document, &mut errors);
+	ids(document, &mut errors);
+
+	errors
+}
@@ -0,0 +1,141 @@
+use maud::{html, Markup, PreEscaped};
+
+use crate::base::base;
+use crate::blog::{latest, Blog};
+use crate::icon::Icon;
+
+// Every article, both all at once grouped by month ('/archive') and a page at a time ('/articles/page/<n>').
+
+pub const PAGE_SIZE: usize = 10;
+
+const MONTHS: &[&str] = &[
+	"January",
+	"February",
+	"March",
+	"April",
+	"May",
+	"June",
+	"July",
+	"August",
+	"September",
+	"October",
+	"November",
+	"December",
+];
+
+fn page(title: &str, description: &str, content: Markup) -> Markup {
+	let schema = format!(
+		r#"{{
+		"@context": "http://schema.org",
+		"@type": "CollectionPage",
+		"name": "{}"
+	}}"#,
+		title
+	);
+
+	base(title, description, PreEscaped(&schema), &[], html! {
+		a.go-back href="/" {
+			(Icon::Back)
+			p { "Main page" }
+		}
+		main.blog-container {
+			h1.blog-title { (title) }
+			(content)
+		}
+	})
+}
+
+// Consecutive runs of posts (which are sorted newest first) sharing the same key.
+
+fn group_by<K: PartialEq>(blogs: &[&'static Blog], key: impl Fn(&Blog) -> K) -> Vec<Vec<&'static Blog>> {
+	let mut groups: Vec<Vec<&'static Blog>> = Vec::new();
+
+	for &blog in blogs {
+		match groups.last_mut() {
+			Some(group) if key(group[0]) == key(blog) => group.push(blog),
+			_ => groups.push(vec![blog]),
+		}
+	}
+
+	groups
+}
+
+fn count(blogs: &[&Blog]) -> String {
+	match blogs.len() {
+		1 => "1 article".to_string(),
+		n => format!("{} articles", n),
+	}
+}
+
+#[get("/archive")]
+pub fn archive() -> Markup {
+	let blogs = latest();
+	let years = group_by(&blogs, |blog| blog.date_key().0);
+
+	page("Archive", "Every article I've written, by month.", html! {
+		p {
+			(count(&blogs)) " in total (or "
+			a.link href="/articles/page/1" { "a page at a time" }
+			")."
+		}
+		@for year in years {
+			section.archive-year {
+				h2 { (year[0].date_key().0) " " small { "(" (count(&year)) ")" } }
+				@for month in group_by(&year, |blog| blog.date_key().1) {
+					h3 { (MONTHS[month[0].date_key().1 as usize - 1]) " " small { "(" (count(&month)) ")" } }
+					ul {
+						@for blog in month {
+							li {
+								a.link href=(blog.route) { (blog.title) }
+							}
+						}
+					}
+				}
+			}
+		}
+	})
+}
+
+pub fn page_count() -> usize {
+	latest().len().div_ceil(PAGE_SIZE).max(1)
+}
+
+#[get("/articles/page/<n>")]
+pub fn articles_page(n: usize) -> Option<Markup> {
+	let pages = page_count();
+
+	if n == 0 || n > pages {
+		return None;
+	}
+
+	let blogs = latest();
+	let blogs = &blogs[(n - 1) * PAGE_SIZE..(n * PAGE_SIZE).min(blogs.len())];
+
+	let title = format!("Articles (page {} of {})", n, pages);
+
+	Some(page(&title, "Every article I've written, a page at a time.", html! {
+		@for blog in blogs {
+			(blog.render_entry())
+			hr;
+		}
+		@if pages > 1 {
+			nav.prev-next aria-label="Pages" {
+				@if n > 1 {
+					a.prev href=(format!("/articles/page/{}", n - 1)) rel="prev" {
+						small { "Previous" }
+						"Newer articles"
+					}
+				}
+				@if n < pages {
+					a.next href=(format!("/articles/page/{}", n + 1)) rel="next" {
+						small { "Next" }
+						"Older articles"
+					}
+				}
+			}
+		}
+		p {
+			a.link href="/archive" { "Archive" }
+		}
+	}))
+}
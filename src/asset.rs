@@ -0,0 +1,63 @@
+use maud::{html, Markup, Render};
+
+use crate::common::str_eq;
+
+// Generated by 'build.rs' from 'public/'.
+
+include!(concat!(env!("OUT_DIR"), "/integrity.rs"));
+
+// Integrity hash of a script or stylesheet in 'public/', looked up at compile time by 'asset!'.
+
+pub const fn integrity(path: &str) -> &'static str {
+	let mut i = 0;
+
+	while i < INTEGRITY.len() {
+		if str_eq(INTEGRITY[i].0, path) {
+			return INTEGRITY[i].1;
+		}
+
+		i += 1;
+	}
+
+	panic!("no integrity hash for this asset, only scripts and stylesheets have one");
+}
+
+// Script or stylesheet in 'public/' (going by its extension), with its SRI hash.
+// Use 'asset!' rather than constructing this directly, so that the build fails if the asset doesn't exist or has no hash.
+
+pub struct Asset {
+	pub path: &'static str,
+	pub integrity: &'static str,
+}
+
+impl Render for Asset {
+	fn render(&self) -> Markup {
+		let src = format!("/public{}", self.path);
+
+		// Assets are served straight from disk, so the hashes computed at build time go stale on the dev server as soon as they're edited.
+
+		let integrity = (!cfg!(feature = "dev")).then_some(self.integrity);
+
+		html! {
+			@if self.path.ends_with(".css") {
+				link rel="stylesheet" href=(src) integrity=[integrity];
+			} @else {
+				script src=(src) integrity=[integrity] defer {}
+			}
+		}
+	}
+}
+
+macro_rules! asset {
+	($path:expr) => {{
+		const _: &[u8] = include_bytes!($crate::common::relative!(concat!("/public", $path)));
+		const INTEGRITY: &str = $crate::asset::integrity($path);
+
+		$crate::asset::Asset {
+			path: $path,
+			integrity: INTEGRITY,
+		}
+	}};
+}
+
+pub(crate) use asset;
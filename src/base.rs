@@ -1,28 +1,76 @@
 use css_minify::optimizations::{Level, Minifier};
 use maud::{html, Markup, PreEscaped, DOCTYPE};
 
-use crate::common::{include_css, include_static_unsafe, relative};
+use crate::asset::asset;
+use crate::common::{include_css, include_static_unsafe};
+use crate::icon::sprite;
+use crate::person::{mentions_schema, Person};
+use crate::security::content_security_policy_meta;
+use crate::site;
 
-pub fn base(title: &str, description: &str, schema: PreEscaped<&str>, content: Markup) -> Markup {
+// Inline, rather than linked, so that pages render in one go.
+// Trimmed, as minifying the page would otherwise trim it, and then it wouldn't match its hash in the CSP anymore.
+
+pub fn main_css() -> PreEscaped<String> {
+	PreEscaped(include_css!("/main.css").0.trim().to_string())
+}
+
+// 'people' is everyone mentioned on the page (see 'person::mentions').
+
+pub fn base(title: &str, description: &str, schema: PreEscaped<&str>, people: &[&Person], content: Markup) -> Markup {
+	page(title, description, schema, None, people, content)
+}
+
+// For pages which are the same as another one, so that search engines only index that one.
+
+pub fn base_canonical(
+	title: &str,
+	description: &str,
+	schema: PreEscaped<&str>,
+	canonical: &str,
+	people: &[&Person],
+	content: Markup,
+) -> Markup {
+	page(title, description, schema, Some(canonical), people, content)
+}
+
+fn page(
+	title: &str,
+	description: &str,
+	schema: PreEscaped<&str>,
+	canonical: Option<&str>,
+	people: &[&Person],
+	content: Markup,
+) -> Markup {
 	assert!(
 		description.len() <= 275,
 		"description is too long, as per Google's 2017 limit on the SERP"
 	);
 
+	let favicon_sizes: Vec<String> = site::FAVICON_SIZES
+		.iter()
+		.map(|size| format!("{0}x{0}", size))
+		.collect();
+
 	html! {
 		(DOCTYPE)
 
 		html lang="en" {
 			head {
 				meta charset="UTF-8"; // must be in the first 1024 bytes of the document
+				meta http-equiv="Content-Security-Policy" content=(content_security_policy_meta(&content.0));
 				meta name="description" content=(description);
 				meta name="viewport" content="width=device-width,initial-scale=1";
 				meta name="robots" content="index,follow";
+				@if let Some(canonical) = canonical {
+					link rel="canonical" href=(format!("{}{}", site::URL, canonical));
+				}
 				meta name="google-site-verification" content="fAAF9QVbOi5rD1tThBbfzVtfhyAFbl4iN2LR42G67TI";
-				meta name="theme-color" content="#000000";
+				meta name="theme-color" content=(site::THEME_COLOUR);
 
-				link rel="icon" type="image/png" href="/public/icons/me.png";
-				link rel="manifest" href="manifest.json";
+				link rel="icon" href="/favicon.ico" sizes=(favicon_sizes.join(" "));
+				link rel="icon" type="image/png" href=(format!("/public{}", site::ICON));
+				link rel="manifest" href="/manifest.webmanifest";
 
 				// Apple PWA stuff
 
@@ -30,19 +78,41 @@ pub fn base(title: &str, description: &str, schema: PreEscaped<&str>, content: M
 				meta name="apple-mobile-web-app-status-bar-style" content="black-translucent";
 				meta name="apple-mobile-web-app-title" content=(title);
 
-				// TODO keywords, apple-touch-startup-image
+				link rel="apple-touch-icon" href="/apple-touch-icon.png" sizes=(format!("{0}x{0}", site::APPLE_TOUCH_ICON_SIZE));
+
+				@for (width, height, ratio) in site::STARTUP_IMAGES {
+					link rel="apple-touch-startup-image" href=(format!("/pwa/startup-{}x{}.png", width, height)) media=(format!(
+						"(device-width: {}px) and (device-height: {}px) and (-webkit-device-pixel-ratio: {}) and (orientation: portrait)",
+						width / ratio,
+						height / ratio,
+						ratio
+					));
+				}
+
+				// TODO keywords
 
 				title { (title) }
 				script type="application/ld+json" { (schema) }
 
-				// link rel="stylesheet" type="text/css" href="/public/main.css";
+				@if !people.is_empty() {
+					script type="application/ld+json" { (PreEscaped(mentions_schema(people))) }
+				}
+
+				// The service worker would only get in the way of seeing changes on the dev server.
+
+				@if cfg!(feature = "dev") {
+					(asset!("/livereload.js"))
+				} @else {
+					(asset!("/sw-register.js"))
+				}
 
 				style {
-					(include_css!("/main.css"))
+					(main_css())
 				}
 			}
 
 			body {
+				(sprite(&content.0))
 				(content)
 			}
 		}
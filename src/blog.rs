@@ -1,9 +1,25 @@
+use std::sync::{Arc, RwLock};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use maud::{html, Markup, PreEscaped};
+use rocket::fairing::AdHoc;
+use rocket::http::uri::{fmt, Segments};
+use rocket::http::{Header, Status};
+use rocket::request::FromSegments;
 use rocket::route::Outcome;
+use rocket::time::OffsetDateTime;
+use rocket::State;
+use sha2::{Digest, Sha256};
 
 use crate::base::base;
-use crate::common::{include_md, include_static, include_static_unsafe, relative, Markdown};
-use crate::social::social;
+use crate::common::{include_md, Markdown, Source};
+use crate::config::Config;
+use crate::history::{commit_url, edit_url, history, history_url, source_url};
+use crate::icon::Icon;
+use crate::person::{Person, SUPERM1};
+use crate::related::{Item, Related};
+use crate::social::FREEBSD_EMAIL;
 
 fn blog_tag(key: &str, val: &str) -> Markup {
 	html! {
@@ -14,17 +30,185 @@ fn blog_tag(key: &str, val: &str) -> Markup {
 	}
 }
 
+// Posts which build on one another, listed together on each of them.
+
+pub struct Series {
+	pub title: &'static str,
+}
+
+pub const FREEBSD_LAPTOPS: Series = Series {
+	title: "FreeBSD on laptops",
+};
+
+// Tests can pretend it's another day, to see scheduled posts go live.
+// That's only for the thread doing so, so that it doesn't get in the way of the others.
+
+#[cfg(test)]
+thread_local! {
+	pub static TODAY: std::cell::Cell<Option<&'static str>> = const { std::cell::Cell::new(None) };
+}
+
+fn today() -> String {
+	#[cfg(test)]
+	if let Some(today) = TODAY.get() {
+		return today.to_string();
+	}
+
+	OffsetDateTime::now_utc().date().to_string()
+}
+
 pub struct Blog {
-	route: &'static str,
-	title: &'static str,
+	pub route: &'static str,
+	pub title: &'static str,
 	descr: &'static str,
 	reading_time: u32,
 	date: &'static str,
-	content: Markdown<&'static str>,
+	pub source: &'static str, // Path of the Markdown in 'public/', for its history.
+	content: Markdown<Source>,
+	series: Option<&'static Series>,
+	pub tags: &'static [&'static str],
+	pub people: &'static [&'static Person], // Everyone mentioned in it (see 'person::mentions').
+	draft: bool,
+	publish_at: Option<&'static str>, // As 'YYYY-MM-DD' (UTC), so that it can be compared as a string.
 }
 
 impl Blog {
-	fn render(&self) -> Markup {
+	// Unpublished posts (drafts, or scheduled ones whose time hasn't come yet) can only be seen through their preview link.
+
+	pub fn published(&self) -> bool {
+		!self.draft && self.publish_at.is_none_or(|publish_at| *publish_at <= *today())
+	}
+
+	// Unguessable (without the secret) token for the preview link, which stays the same across restarts so it can be shared.
+
+	pub fn preview_token(&self, secret: &str) -> String {
+		let hash = Sha256::new().chain_update(secret).chain_update(self.route).finalize();
+		URL_SAFE_NO_PAD.encode(&hash[..16])
+	}
+
+	fn banner(&self) -> Markup {
+		html! {
+			.draft-banner role="note" {
+				b { "Draft" }
+				@match self.publish_at {
+					Some(publish_at) if !self.draft => { "This post will be published on " (publish_at) "." }
+					_ => { "This post hasn't been published yet." }
+				}
+				" Please don't share this link."
+			}
+		}
+	}
+
+	// Dates are written as 'D/M/YYYY', so turn them into something which sorts chronologically.
+
+	pub fn date_key(&self) -> (u32, u32, u32) {
+		let mut parts = self.date.split('/').map(|part| part.parse().unwrap());
+		let (day, month, year) = (parts.next().unwrap(), parts.next().unwrap(), parts.next().unwrap());
+
+		(year, month, day)
+	}
+
+	// The posts this one sits between, in date order: the other parts of its series, or otherwise every post.
+	// Unpublished posts only ever see themselves in there, so that previews look like the real thing.
+
+	fn neighbours(&self) -> Vec<&'static Blog> {
+		let mut neighbours: Vec<&'static Blog> = BLOGS
+			.iter()
+			.copied()
+			.filter(|blog| blog.published() || blog.route == self.route)
+			.filter(|blog| match (self.series, blog.series) {
+				(Some(series), Some(other)) => series.title == other.title,
+				(Some(_), None) => false,
+				(None, _) => true,
+			})
+			.collect();
+
+		neighbours.sort_by_key(|blog| blog.date_key());
+		neighbours
+	}
+
+	fn series_box(&self, series: &Series, parts: &[&Blog]) -> Markup {
+		let part = parts.iter().position(|blog| blog.route == self.route).unwrap() + 1;
+
+		html! {
+			aside.series aria-label="Series" {
+				p {
+					"This is part " (part) " of " (parts.len()) " in the series "
+					b { (series.title) }
+					"."
+				}
+				ol {
+					@for blog in parts {
+						li {
+							@if blog.route == self.route {
+								span aria-current="page" { (blog.title) }
+							} @else {
+								a.link href=(blog.route) { (blog.title) }
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	fn prev_next(&self, neighbours: &[&Blog]) -> Markup {
+		let i = neighbours.iter().position(|blog| blog.route == self.route).unwrap();
+		let prev = i.checked_sub(1).map(|i| neighbours[i]);
+		let next = neighbours.get(i + 1);
+
+		html! {
+			@if prev.is_some() || next.is_some() {
+				nav.prev-next aria-label="More posts" {
+					@if let Some(prev) = prev {
+						a.prev href=(prev.route) rel="prev" {
+							small { "Previous" }
+							(prev.title)
+						}
+					}
+					@if let Some(next) = next {
+						a.next href=(next.route) rel="next" {
+							small { "Next" }
+							(next.title)
+						}
+					}
+				}
+			}
+		}
+	}
+
+	pub fn content(&self) -> &Markdown<Source> {
+		&self.content
+	}
+
+	fn related(&self, related: &[Item]) -> Markup {
+		html! {
+			@if !related.is_empty() {
+				aside.related aria-labelledby="related" {
+					h2 #related { "Related" }
+					ul {
+						@for item in related {
+							li {
+								a.link href=(item.link()) { (item.title()) }
+							}
+						}
+					}
+				}
+			}
+		}
+	}
+
+	pub fn history_route(&self) -> String {
+		format!("{}/history", self.route)
+	}
+
+	// The post's newest revision, or when it was published if we don't have its history.
+
+	fn updated(&self) -> &'static str {
+		history(self.source).first().map_or(self.date, |revision| revision.date)
+	}
+
+	fn render(&self, related: &[Item]) -> Markup {
 		let schema = format!(
 			r#"{{
 			"@context": "http://schema.org",
@@ -36,19 +220,88 @@ impl Blog {
 			self.title
 		);
 
-		base(self.title, self.descr, PreEscaped(&schema), html! {
+		let neighbours = self.neighbours();
+
+		base(self.title, self.descr, PreEscaped(&schema), self.people, html! {
 			a.go-back href="/" {
-				(include_static!("/icons/back.svg"))
+				(Icon::Back)
 				p { "Main page" }
 			}
-			.blog-container {
+			main.blog-container {
+				@if !self.published() {
+					(self.banner())
+				}
 				h1.blog-title { (self.title) }
 				(blog_tag("Reading time:", &format!("{} min", self.reading_time)))
 				(blog_tag("Date published:", self.date))
+				@if self.published() {
+					.blog-tag.last-updated {
+						b { "Last updated:" }
+						a.link href=(self.history_route()) { (self.updated()) }
+					}
+				}
 				hr;
+				@if let Some(series) = self.series {
+					(self.series_box(series, &neighbours))
+				}
 				(self.content)
+				@if self.published() {
+					p.blog-source {
+						a.link href=(edit_url(self.source)) { "Suggest an edit" }
+						" · "
+						a.link href=(source_url(self.source)) { "View source on GitHub" }
+						" · "
+						a.link href=(self.history_route()) { "Revision history" }
+					}
+				}
+				(self.related(related))
+				(self.prev_next(&neighbours))
 				.socials {
-					(social("obiwac@freebsd.org", "mailto:obiwac@freebsd.org", include_static!("/icons/fbsd.svg")))
+					(FREEBSD_EMAIL)
+				}
+			}
+		})
+	}
+
+	fn render_history(&self) -> Markup {
+		let title = format!("History of {}", self.title);
+		let description = format!("Every revision of \"{}\" since it was first written.", self.title);
+		let revisions = history(self.source);
+
+		let schema = format!(
+			r#"{{
+			"@context": "http://schema.org",
+			"@type": "WebPage",
+			"name": "{}"
+		}}"#,
+			title
+		);
+
+		base(&title, &description, PreEscaped(&schema), &[], html! {
+			a.go-back href=(self.route) {
+				(Icon::Back)
+				p { "Back to the post" }
+			}
+			main.blog-container {
+				h1.blog-title { (title) }
+				.history {
+					@if revisions.is_empty() {
+						p { "No history is available for this post." }
+					} @else {
+						ol {
+							@for revision in revisions {
+								li {
+									a.link href=(commit_url(revision)) {
+										code { (&revision.hash[..7]) }
+									}
+									" " (revision.summary) " (" (revision.date) ")"
+								}
+							}
+						}
+					}
+				}
+				p.blog-source {
+					a.link href=(history_url(self.source)) { "Full history on GitHub" }
 				}
 			}
 		})
@@ -76,7 +329,13 @@ pub const BLOGS: &'static [&'static Blog] = &[
 		        superseeds the previous ACPI S3 sleep state.",
 		reading_time: 12,
 		date: "1/11/2024",
+		source: "/blog/s0ix.md",
 		content: include_md!("/blog/s0ix.md"),
+		series: Some(&FREEBSD_LAPTOPS),
+		tags: &["freebsd", "laptops", "power management", "acpi", "kernel"],
+		people: &[&SUPERM1],
+		draft: false,
+		publish_at: None,
 	},
 	&Blog {
 		route: "/fprint",
@@ -85,20 +344,196 @@ pub const BLOGS: &'static [&'static Blog] = &[
 		        the general software architecture and a few use cases.",
 		reading_time: 5,
 		date: "12/10/2024",
+		source: "/blog/fprint.md",
 		content: include_md!("/blog/fprint.md"),
+		series: Some(&FREEBSD_LAPTOPS),
+		tags: &["freebsd", "laptops", "security", "drivers"],
+		people: &[],
+		draft: false,
+		publish_at: None,
+	},
+	// Only there for the tests, which check that drafts don't show up anywhere but through their preview link.
+	// Its Markdown isn't in 'public/', so that it can't end up in the export either.
+	#[cfg(test)]
+	&Blog {
+		route: "/draft-fixture",
+		title: "Draft fixture 🚧",
+		descr: "Post which is never published.",
+		reading_time: 1,
+		date: "1/1/2025",
+		source: "/../src/fixtures/draft.md",
+		content: include_md!("/../src/fixtures/draft.md"),
+		series: None,
+		tags: &["freebsd", "laptops"],
+		people: &[],
+		draft: true,
+		publish_at: None,
+	},
+	// Same as the draft fixture, but for checking that scheduled posts show up everywhere once their time has come.
+	#[cfg(test)]
+	&Blog {
+		route: "/scheduled-fixture",
+		title: "Scheduled fixture ⏰",
+		descr: "Post which is only published in the far future.",
+		reading_time: 1,
+		date: "1/1/2100",
+		source: "/../src/fixtures/scheduled.md",
+		content: include_md!("/../src/fixtures/scheduled.md"),
+		series: None,
+		tags: &["freebsd", "laptops"],
+		people: &[],
+		draft: false,
+		publish_at: Some("2100-01-01"),
 	},
 ];
 
+// Everything which should be listed or exported.
+
+pub fn published() -> impl Iterator<Item = &'static Blog> {
+	BLOGS.iter().copied().filter(|blog| blog.published())
+}
+
+// Something worked out from the published posts (e.g. related posts), which is worked out again whenever they change, i.e. when a scheduled post goes live.
+// Dev builds read posts from disk on every request, so it's worked out again every time there.
+
+pub struct PerPublished<T> {
+	build: fn() -> T,
+	built: RwLock<(Vec<&'static str>, Arc<T>)>, // Routes of the posts it was worked out from.
+}
+
+impl<T> PerPublished<T> {
+	pub fn new(build: fn() -> T) -> Self {
+		let routes = published().map(|blog| blog.route).collect();
+
+		PerPublished {
+			build,
+			built: RwLock::new((routes, Arc::new(build()))),
+		}
+	}
+
+	pub fn current(&self) -> Arc<T> {
+		if cfg!(feature = "dev") {
+			return Arc::new((self.build)());
+		}
+
+		let routes: Vec<&str> = published().map(|blog| blog.route).collect();
+		let built = self.built.read().unwrap();
+
+		if built.0 == routes {
+			return built.1.clone();
+		}
+
+		drop(built);
+
+		let built = Arc::new((self.build)());
+		*self.built.write().unwrap() = (routes, built.clone());
+		built
+	}
+}
+
+// Published posts, newest first.
+
+pub fn latest() -> Vec<&'static Blog> {
+	let mut blogs: Vec<&'static Blog> = published().collect();
+	blogs.sort_by_key(|blog| std::cmp::Reverse(blog.date_key()));
+	blogs
+}
+
+// Routes for unpublished posts are still mounted, so that scheduled posts go live without a restart, but they 404 until then.
+
 pub fn blog_routes() -> Vec<rocket::Route> {
 	BLOGS
 		.iter()
-		.map(|blog| {
+		.flat_map(|blog| {
 			let handler =
 				for<'r, 'x> move |req: &'r rocket::Request<'x>, _: rocket::Data<'r>| -> rocket::route::BoxFuture<'r> {
-					Outcome::from(req, blog.render()).pin()
+					if !blog.published() {
+						return Outcome::Error(Status::NotFound).pin();
+					}
+
+					let related = req.rocket().state::<PerPublished<Related>>().unwrap().current();
+					Outcome::from(req, blog.render(related.of(blog))).pin()
+				};
+
+			let history_handler =
+				for<'r, 'x> move |req: &'r rocket::Request<'x>, _: rocket::Data<'r>| -> rocket::route::BoxFuture<'r> {
+					if !blog.published() {
+						return Outcome::Error(Status::NotFound).pin();
+					}
+
+					Outcome::from(req, blog.render_history()).pin()
 				};
 
-			rocket::route::Route::new(rocket::http::Method::Get, blog.route, handler)
+			[
+				rocket::route::Route::new(rocket::http::Method::Get, blog.route, handler),
+				rocket::route::Route::new(rocket::http::Method::Get, &blog.history_route(), history_handler),
+			]
 		})
 		.collect()
 }
+
+// Posts' Markdown is only ever served rendered, as the sources of unpublished ones would otherwise be out in the open under 'public/'.
+// 'gen_static.sh' leaves them out of the static export for the same reason.
+
+pub struct MarkdownSource;
+
+impl<'r> FromSegments<'r> for MarkdownSource {
+	type Error = ();
+
+	fn from_segments(segments: Segments<'r, fmt::Path>) -> Result<Self, ()> {
+		match segments.last() {
+			Some(file) if file.ends_with(".md") => Ok(MarkdownSource),
+			_ => Err(()),
+		}
+	}
+}
+
+// Ranked above the file server (which is at 10), which anything that isn't Markdown is forwarded on to.
+
+#[get("/public/<_source..>", rank = 9)]
+pub fn markdown_source(_source: MarkdownSource) -> Status {
+	Status::NotFound
+}
+
+// Previews are only available when a secret is set (through 'ROCKET_PREVIEW_SECRET', so that it doesn't end up in the repo).
+// They're kept out of search engines just in case a link leaks.
+
+#[derive(Responder)]
+pub struct Preview {
+	page: Markup,
+	robots: Header<'static>,
+}
+
+#[get("/preview/<token>")]
+pub fn preview(token: &str, config: &State<Config>) -> Option<Preview> {
+	let secret = config.preview_secret.as_deref()?;
+	let blog = BLOGS
+		.iter()
+		.find(|blog| !blog.published() && blog.preview_token(secret) == token)?;
+
+	// Unpublished posts aren't part of what's related to what, so there's nothing to suggest here.
+
+	Some(Preview {
+		page: blog.render(&[]),
+		robots: Header::new("X-Robots-Tag", "noindex"),
+	})
+}
+
+// Log the preview links on launch, as there's no other way of finding them out.
+
+pub fn preview_links() -> AdHoc {
+	AdHoc::on_liftoff("Preview links", |rocket| {
+		Box::pin(async move {
+			let Some(secret) = rocket
+				.state::<Config>()
+				.and_then(|config| config.preview_secret.as_deref())
+			else {
+				return;
+			};
+
+			for blog in BLOGS.iter().filter(|blog| !blog.published()) {
+				info!("Preview of {}: /preview/{}", blog.route, blog.preview_token(secret));
+			}
+		})
+	})
+}
@@ -0,0 +1,145 @@
+use maud::{html, Markup, PreEscaped};
+use rocket::http::Status;
+use rocket::Request;
+
+use crate::base::base;
+use crate::common::levenshtein;
+use crate::icon::Icon;
+use crate::index::{PROJECTS, SIDE_PROJECTS};
+use crate::person::PEOPLE;
+use crate::{blog, search, site};
+
+// Every page we know about along with its title, to suggest to people who got lost.
+
+fn known_pages() -> Vec<(String, &'static str)> {
+	let mut pages = vec![("/".to_string(), site::NAME)];
+
+	pages.extend(
+		PROJECTS
+			.iter()
+			.chain(SIDE_PROJECTS.iter())
+			.filter(|project| project.link.starts_with('/'))
+			.map(|project| (project.link.to_string(), project.title)),
+	);
+
+	pages.extend(blog::published().map(|blog| (blog.route.to_string(), blog.title)));
+	pages.extend(PEOPLE.iter().map(|person| (person.route(), person.name)));
+
+	pages
+}
+
+// How far off 'path' is from a page, either by its route (e.g. '/s0i3' for '/s0ix') or by a word in its title (e.g. '/fingerprint').
+// Returns 'None' if it's too far off to be worth suggesting.
+
+fn distance(path: &str, route: &str, title: &str) -> Option<usize> {
+	let route_distance = levenshtein(path, route);
+	let route_threshold = route.len().max(path.len()) / 3;
+
+	let title = title.to_lowercase();
+	let words: Vec<&str> = title
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| word.len() >= 3)
+		.collect();
+
+	let title_distance = path
+		.split(|c: char| !c.is_alphanumeric())
+		.filter(|token| token.len() >= 3)
+		.flat_map(|token| {
+			words
+				.iter()
+				.map(move |word| levenshtein(token, word))
+				.filter(move |distance| *distance <= token.len() / 4)
+		})
+		.min();
+
+	match (route_distance <= route_threshold, title_distance) {
+		(true, Some(title_distance)) => Some(route_distance.min(title_distance)),
+		(true, None) => Some(route_distance),
+		(false, title_distance) => title_distance,
+	}
+}
+
+fn suggestions(path: &str) -> Vec<(String, &'static str)> {
+	let path = path.to_lowercase();
+	let path = path.trim_end_matches('/');
+
+	let mut suggestions: Vec<(usize, (String, &'static str))> = known_pages()
+		.into_iter()
+		.filter_map(|(route, title)| distance(path, &route, title).map(|distance| (distance, (route, title))))
+		.collect();
+
+	suggestions.sort_by_key(|(distance, _)| *distance);
+	suggestions.into_iter().take(3).map(|(_, page)| page).collect()
+}
+
+fn error_page(title: &str, description: &str, content: Markup) -> Markup {
+	let schema = format!(
+		r#"{{
+		"@context": "http://schema.org",
+		"@type": "WebPage",
+		"name": "{}"
+	}}"#,
+		title
+	);
+
+	base(title, description, PreEscaped(&schema), &[], html! {
+		a.go-back href="/" {
+			(Icon::Back)
+			p { "Main page" }
+		}
+		main.blog-container {
+			h1.blog-title { (title) }
+			(content)
+		}
+	})
+}
+
+// Without a path (see 'static_not_found'), there's nothing to say about what was asked for, let alone to suggest.
+
+fn not_found_page(path: Option<&str>) -> Markup {
+	let suggestions = path.map(suggestions).unwrap_or_default();
+
+	error_page("Page not found 🔍", "This page doesn't exist (anymore?)", html! {
+		p {
+			@if let Some(path) = path {
+				"There's nothing at "
+				code { (path) }
+				"."
+			} @else {
+				"There's nothing here."
+			}
+		}
+		@if !suggestions.is_empty() {
+			p { "Did you mean:" }
+			ul {
+				@for (route, title) in suggestions {
+					li {
+						a.link href=(route) { (title) }
+					}
+				}
+			}
+		}
+		p { "Or try searching for it:" }
+		(search::form(""))
+	})
+}
+
+#[catch(404)]
+pub fn not_found(req: &Request) -> Markup {
+	not_found_page(Some(req.uri().path().as_str()))
+}
+
+// What the static export uses as its '404.html', which GitHub Pages serves for any path it doesn't know about.
+// Since the same file is served whatever the path, it's the generic version.
+
+#[get("/404.html")]
+pub fn static_not_found() -> (Status, Markup) {
+	(Status::NotFound, not_found_page(None))
+}
+
+#[catch(500)]
+pub fn internal_error() -> Markup {
+	error_page("Something went wrong 💥", "Internal server error", html! {
+		p { "Something went wrong on my end while rendering this page. Sorry about that!" }
+	})
+}
@@ -1,5 +1,6 @@
-use maud::{Markup, PreEscaped, Render};
-use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use latex2mathml::{latex_to_mathml, DisplayStyle};
+use maud::{html, Markup, PreEscaped, Render};
+use pulldown_cmark::{html, BlockQuoteKind, CowStr, Event, Options, Parser, Tag, TagEnd};
 
 macro_rules! relative {
 	($path:expr) => {
@@ -7,15 +8,25 @@ macro_rules! relative {
 	};
 }
 
+// Files in 'public/' are embedded at compile time, except in dev builds where they're read from disk each time they're used, so that editing them doesn't need a recompile.
+
+#[cfg(not(feature = "dev"))]
+macro_rules! include_static_unsafe {
+	($path:expr) => {
+		include_str!($crate::common::relative!(concat!("/public", $path)))
+	};
+}
+
+#[cfg(feature = "dev")]
 macro_rules! include_static_unsafe {
 	($path:expr) => {
-		include_str!(relative!(concat!("/public", $path)))
+		$crate::dev::read($crate::common::relative!(concat!("/public", $path)))
 	};
 }
 
 macro_rules! include_static {
 	($path:expr) => {
-		PreEscaped(include_static_unsafe!($path))
+		PreEscaped(&*include_static_unsafe!($path))
 	};
 }
 
@@ -23,26 +34,181 @@ macro_rules! include_css {
 	($path:expr) => {
 		PreEscaped(
 			Minifier::default()
-				.minify(include_static_unsafe!($path), Level::Three)
+				.minify(&include_static_unsafe!($path), Level::Three)
 				.unwrap(),
 		)
 	};
 }
 
+#[cfg(not(feature = "dev"))]
+macro_rules! include_md {
+	($path:expr) => {
+		Markdown($crate::common::include_static_unsafe!($path))
+	};
+}
+
+// Markdown is usually included in constants (e.g. 'BLOGS'), so only hold on to the path until it's rendered.
+
+#[cfg(feature = "dev")]
 macro_rules! include_md {
 	($path:expr) => {
-		Markdown(include_static_unsafe!($path))
+		Markdown($crate::common::Source($crate::common::relative!(concat!(
+			"/public", $path
+		))))
 	};
 }
 
 pub(crate) use {include_css, include_md, include_static, include_static_unsafe, relative};
 
+// 'str' comparison which can be used in constants, which '==' can't (yet).
+
+pub const fn str_eq(a: &str, b: &str) -> bool {
+	let (a, b) = (a.as_bytes(), b.as_bytes());
+
+	if a.len() != b.len() {
+		return false;
+	}
+
+	let mut i = 0;
+
+	while i < a.len() {
+		if a[i] != b[i] {
+			return false;
+		}
+
+		i += 1;
+	}
+
+	true
+}
+
 pub struct Markdown<T>(pub T);
 
+// What 'include_md!' gives us: the Markdown itself, or in dev builds, the path to read it from.
+
+#[cfg(not(feature = "dev"))]
+pub type Source = &'static str;
+
+#[cfg(feature = "dev")]
+pub struct Source(pub &'static str);
+
+#[cfg(feature = "dev")]
+impl Render for Markdown<Source> {
+	fn render(&self) -> Markup {
+		Markdown(crate::dev::read(self.0 .0)).render()
+	}
+}
+
+// Math ('$...$' and '$$...$$') is rendered to MathML right here, so that pages don't need any JS for it.
+// Display equations with a '\label{name}' are numbered in order ('number'), and can be referred to from any math with '\eqref{name}'.
+// References are swapped out for placeholders which latex2mathml leaves alone, and then for the equation's number, which is a link unless the math is in one already.
+
+fn math(latex: &str, display: DisplayStyle, number: impl Fn(&str) -> Result<usize, String>, link: bool) -> String {
+	let mut placeheld = latex.to_string();
+	let mut references = Vec::new();
+
+	while let Some((before, label, after)) = split_command(&placeheld, "eqref") {
+		let reference = match (number(label), link) {
+			(Ok(number), true) => format!("<a class=\"link\" href=\"#eq-{}\">({})</a>", label, number),
+			(Ok(number), false) => format!("({})", number),
+			(Err(err), _) => return math_error(latex, &err),
+		};
+
+		// latex2mathml only takes letters in '\text{...}'.
+
+		let placeholder: String = references
+			.len()
+			.to_string()
+			.bytes()
+			.map(|digit| (digit - b'0' + b'a') as char)
+			.collect();
+
+		references.push((
+			format!("<mtext>EQREF{}</mtext>", placeholder),
+			format!("<mtext>{}</mtext>", reference),
+		));
+
+		placeheld = format!("{}\\text{{EQREF{}}}{}", before, placeholder, after);
+	}
+
+	// latex2mathml renders most of what it doesn't understand anyway, with the error in the middle of it.
+
+	let mathml = match latex_to_mathml(&placeheld, display) {
+		Ok(mathml) => match mathml.split_once("[PARSE ERROR: ") {
+			Some((_, err)) => return math_error(latex, err.split_once("]</mtext>").map_or(err, |(err, _)| err)),
+			None => mathml,
+		},
+		Err(err) => return math_error(latex, &err.to_string()),
+	};
+
+	references.iter().fold(mathml, |mathml, (placeholder, reference)| {
+		mathml.replace(placeholder, reference)
+	})
+}
+
+// Math which can't be rendered is shown as is (and logged), rather than taking the whole page down with it.
+
+fn math_error(latex: &str, err: &str) -> String {
+	warn!("couldn't render \"{}\": {}", latex, err);
+	html! { span.math-error { code { (latex) } } }.into_string()
+}
+
+// Text before the first '\command{...}' in 'latex', its argument, and the text after it.
+
+fn split_command<'a>(latex: &'a str, command: &str) -> Option<(&'a str, &'a str, &'a str)> {
+	let start = latex.find(&format!("\\{}{{", command))?;
+	let rest = &latex[start + command.len() + 2..];
+	let end = rest.find('}')?;
+
+	Some((&latex[..start], &rest[..end], &rest[end + 1..]))
+}
+
+const MATHML_TAGS: &[&str] = &[
+	"math",
+	"mi",
+	"mn",
+	"mo",
+	"mtext",
+	"mspace",
+	"mrow",
+	"mstyle",
+	"mfrac",
+	"msqrt",
+	"mroot",
+	"msub",
+	"msup",
+	"msubsup",
+	"munder",
+	"mover",
+	"munderover",
+	"mmultiscripts",
+	"mtable",
+	"mtr",
+	"mtd",
+];
+
 impl<T: AsRef<str>> Render for Markdown<T> {
 	fn render(&self) -> Markup {
 		let mut unsafe_html = String::new();
-		let parser = Parser::new_ext(self.0.as_ref(), Options::ENABLE_TABLES);
+		let options =
+			Options::ENABLE_TABLES | Options::ENABLE_HEADING_ATTRIBUTES | Options::ENABLE_GFM | Options::ENABLE_MATH;
+
+		// Equations can be referred to before they appear, so number them all up front.
+
+		let labels: Vec<String> = Parser::new_ext(self.0.as_ref(), options)
+			.filter_map(|event| match event {
+				Event::DisplayMath(latex) => split_command(&latex, "label").map(|(_, label, _)| label.to_string()),
+				_ => None,
+			})
+			.collect();
+
+		let number = |label: &str| match labels.iter().filter(|other| *other == label).count() {
+			0 => Err(format!("no equation is labelled \"{}\"", label)),
+			1 => Ok(labels.iter().position(|other| other == label).unwrap() + 1),
+			_ => Err(format!("more than one equation is labelled \"{}\"", label)),
+		};
+
+		let parser = Parser::new_ext(self.0.as_ref(), options);
 
 		// Preprocessor to highlight syntax in code blocks.
 
@@ -56,6 +222,7 @@ impl<T: AsRef<str>> Render for Markdown<T> {
 		let mut new_parser: Vec<Event> = Vec::new();
 
 		let mut in_table = false;
+		let mut blockquotes: Vec<bool> = Vec::new(); // Whether each enclosing blockquote is an admonition.
 
 		while let Some(event) = parser.next() {
 			match event {
@@ -105,6 +272,67 @@ impl<T: AsRef<str>> Render for Markdown<T> {
 					new_parser.push(Event::Html(CowStr::Borrowed("</div>")));
 				}
 
+				// Turn GitHub-style admonitions ('> [!NOTE]' &c) into asides, the icons for which are added in CSS.
+				Event::Start(Tag::BlockQuote(Some(kind))) => {
+					let (class, title) = match kind {
+						BlockQuoteKind::Note => ("note", "Note"),
+						BlockQuoteKind::Tip => ("tip", "Tip"),
+						BlockQuoteKind::Important => ("important", "Important"),
+						BlockQuoteKind::Warning => ("warning", "Warning"),
+						BlockQuoteKind::Caution => ("caution", "Caution"),
+					};
+
+					let html = format!(
+						"<aside class=\"admonition admonition-{}\" role=\"note\"><p class=\"admonition-title\">{}</p>",
+						class, title
+					);
+
+					blockquotes.push(true);
+					new_parser.push(Event::Html(CowStr::Boxed(html.into())));
+				}
+				Event::Start(Tag::BlockQuote(None)) => {
+					blockquotes.push(false);
+					new_parser.push(event);
+				}
+				Event::End(TagEnd::BlockQuote) => match blockquotes.pop().unwrap() {
+					true => new_parser.push(Event::Html(CowStr::Borrowed("</aside>"))),
+					false => new_parser.push(event),
+				},
+
+				// Render math to MathML.
+				Event::InlineMath(latex) => {
+					let html = math(&latex, DisplayStyle::Inline, number, !in_link);
+
+					match in_link {
+						true => link_text.push_str(&html),
+						false => new_parser.push(Event::InlineHtml(CowStr::Boxed(html.into()))),
+					}
+				}
+				Event::DisplayMath(latex) => {
+					let html = match split_command(&latex, "label") {
+						_ if in_link => math_error(&latex, "display math can't go in a link"),
+						Some((before, label, after)) => match number(label) {
+							Ok(n) => format!(
+								"<span class=\"equation\" id=\"eq-{}\">{}<span \
+								 class=\"equation-number\">({})</span></span>",
+								label,
+								math(&format!("{}{}", before, after), DisplayStyle::Block, number, true),
+								n
+							),
+							Err(err) => format!("<span class=\"equation\">{}</span>", math_error(&latex, &err)),
+						},
+						None => format!(
+							"<span class=\"equation\">{}</span>",
+							math(&latex, DisplayStyle::Block, number, true)
+						),
+					};
+
+					match in_link {
+						true => link_text.push_str(&html),
+						false => new_parser.push(Event::InlineHtml(CowStr::Boxed(html.into()))),
+					}
+				}
+
 				// Regular events.
 				_ => {
 					assert!(!in_link);
@@ -120,6 +348,14 @@ impl<T: AsRef<str>> Render for Markdown<T> {
 		// Sanitize unsafe HTML.
 
 		let safe = ammonia::Builder::default()
+			.add_tags(MATHML_TAGS)
+			.add_tag_attributes("math", &["display"])
+			.add_tag_attributes("mi", &["mathvariant"])
+			.add_tag_attributes("mo", &["mathvariant", "accent", "stretchy", "form", "minsize", "maxsize"])
+			.add_tag_attributes("mspace", &["width"])
+			.add_tag_attributes("mstyle", &["displaystyle"])
+			.add_tag_attributes("mfrac", &["linethickness"])
+			.add_tag_attributes("mtable", &["columnalign"])
 			.add_allowed_classes("a", &["link"])
 			.add_allowed_classes("span", &[
 				"glyph",
@@ -129,12 +365,48 @@ impl<T: AsRef<str>> Render for Markdown<T> {
 				"strong-identifier",
 				"keyword",
 				"comment",
+				"equation",
+				"equation-number",
+				"math-error",
 			])
 			.add_allowed_classes("div", &["table"])
+			.add_allowed_classes("aside", &[
+				"admonition",
+				"admonition-note",
+				"admonition-tip",
+				"admonition-important",
+				"admonition-warning",
+				"admonition-caution",
+			])
+			.add_allowed_classes("p", &["admonition-title"])
+			.add_tag_attributes("aside", &["role"])
 			.add_tag_attributes("div", &["style"])
+			.add_tag_attributes("h2", &["id"]) // So that sections can be linked to with '{#id}'.
+			.add_tag_attributes("h3", &["id"])
+			.add_tag_attributes("span", &["id"]) // For equation references.
 			.clean(&unsafe_html)
 			.to_string();
 
 		PreEscaped(safe)
 	}
 }
+
+// Edit distance between two strings, for fuzzy matching.
+
+pub fn levenshtein(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut row: Vec<usize> = (0..=b.len()).collect();
+
+	for (i, a) in a.chars().enumerate() {
+		let mut prev = row[0];
+		row[0] = i + 1;
+
+		for (j, b) in b.iter().enumerate() {
+			let cur = row[j + 1];
+			row[j + 1] = (prev + (a != *b) as usize).min(row[j] + 1).min(cur + 1);
+			prev = cur;
+		}
+	}
+
+	row[b.len()]
+}
@@ -0,0 +1,11 @@
+use rocket::serde::Deserialize;
+
+// Our own settings, read from 'Rocket.toml' (or 'ROCKET_*' environment variables) alongside Rocket's.
+
+#[derive(Deserialize)]
+#[serde(crate = "rocket::serde")]
+pub struct Config {
+	#[serde(default)]
+	pub minify_html: bool,
+	pub preview_secret: Option<String>, // Secret from which draft preview links are derived (see 'blog::preview').
+}
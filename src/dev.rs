@@ -0,0 +1,66 @@
+use std::fs;
+use std::path::Path;
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use rocket::fairing::AdHoc;
+use rocket::response::stream::{Event, EventStream};
+use rocket::tokio::select;
+use rocket::tokio::sync::broadcast::error::RecvError;
+use rocket::tokio::sync::broadcast::{self, Sender};
+use rocket::{Shutdown, State};
+
+use crate::common::relative;
+
+// Development server stuff, only built with the 'dev' feature.
+
+pub fn read(path: &str) -> String {
+	fs::read_to_string(path).unwrap_or_else(|err| panic!("couldn't read \"{}\": {}", path, err))
+}
+
+// Every open page listens on '/__livereload' (see 'public/livereload.js'), and gets told to reload whenever anything in 'public/' changes.
+// The watcher is kept around in here, as it stops watching as soon as it's dropped.
+
+pub struct LiveReload {
+	sender: Sender<()>,
+	_watcher: RecommendedWatcher,
+}
+
+pub fn live_reload() -> AdHoc {
+	AdHoc::on_ignite("Live reload", |rocket| async {
+		let (sender, _) = broadcast::channel(16);
+		let watcher_sender = sender.clone();
+
+		let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+			if event.is_ok_and(|event| !event.kind.is_access()) {
+				let _ = watcher_sender.send(()); // Fails if no page is open, which is fine.
+			}
+		})
+		.expect("couldn't create file watcher");
+
+		watcher
+			.watch(Path::new(relative!("/public")), RecursiveMode::Recursive)
+			.expect("couldn't watch 'public/'");
+
+		rocket.manage(LiveReload {
+			sender,
+			_watcher: watcher,
+		})
+	})
+}
+
+#[get("/__livereload")]
+pub fn livereload(live_reload: &State<LiveReload>, mut shutdown: Shutdown) -> EventStream![] {
+	let mut receiver = live_reload.sender.subscribe();
+
+	EventStream! {
+		loop {
+			select! {
+				message = receiver.recv() => match message {
+					Ok(()) | Err(RecvError::Lagged(_)) => yield Event::data("reload"),
+					Err(RecvError::Closed) => break,
+				},
+				_ = &mut shutdown => break,
+			}
+		}
+	}
+}
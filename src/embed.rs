@@ -0,0 +1,105 @@
+use maud::{html, Markup, Render};
+
+use crate::asset::asset;
+use crate::common::str_eq;
+use crate::project_pages::EMBEDS;
+
+// Third-party iframes are hidden behind a click-to-load facade, so that merely opening a page doesn't make the visitor's browser contact anyone else.
+// The iframe lives in a 'template' (which the browser doesn't load anything from) until 'facade.js' swaps it in.
+
+// Use 'embed!' rather than rendering this directly, so that the build fails if it isn't listed in 'project_pages::EMBEDS'.
+
+pub struct Embed {
+	pub title: &'static str,
+	pub src: &'static str,
+	pub poster: &'static str, // Must be a local file!
+}
+
+impl Embed {
+	pub fn host(&self) -> &'static str {
+		let host = self.src.split_once("://").map_or(self.src, |(_, rest)| rest);
+		let host = host.split('/').next().unwrap_or(host);
+
+		host.strip_prefix("www.").unwrap_or(host)
+	}
+}
+
+impl Render for Embed {
+	fn render(&self) -> Markup {
+		html! {
+			.facade {
+				img alt=(self.title) src=(self.poster) loading="lazy";
+				button type="button" {
+					"Load external content from "
+					strong { (self.host()) }
+				}
+				template {
+					iframe title=(self.title) src=(self.src) allow="accelerometer; autoplay; clipboard-write; encrypted-media; gyroscope; picture-in-picture; web-share" referrerpolicy="strict-origin-when-cross-origin" allowfullscreen {}
+				}
+			}
+			(asset!("/facade.js"))
+		}
+	}
+}
+
+// Check that embeds are listed in 'project_pages::EMBEDS' (see 'embed!') and that their posters are local, at compile time.
+
+pub const fn listed(embed: &Embed) -> bool {
+	let mut i = 0;
+
+	while i < EMBEDS.len() {
+		if str_eq(EMBEDS[i].src, embed.src) {
+			return true;
+		}
+
+		i += 1;
+	}
+
+	false
+}
+
+const fn local(poster: &str) -> bool {
+	let (poster, prefix) = (poster.as_bytes(), b"/public/");
+
+	if poster.len() < prefix.len() {
+		return false;
+	}
+
+	let mut i = 0;
+
+	while i < prefix.len() {
+		if poster[i] != prefix[i] {
+			return false;
+		}
+
+		i += 1;
+	}
+
+	true
+}
+
+const _: () = {
+	let mut i = 0;
+
+	while i < EMBEDS.len() {
+		assert!(
+			local(EMBEDS[i].poster),
+			"embed posters must be served locally, otherwise the facade is pointless"
+		);
+
+		i += 1;
+	}
+};
+
+macro_rules! embed {
+	($embed:expr) => {{
+		const _: () = assert!(
+			$crate::embed::listed(&$embed),
+			"embeds must be listed in 'project_pages::EMBEDS', otherwise the CSP blocks them"
+		);
+
+		$embed
+	}};
+}
+
+pub(crate) use embed;
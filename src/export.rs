@@ -0,0 +1,98 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use rocket::http::{ContentType, Status};
+use rocket::local::asynchronous::Client;
+use rocket::{Build, Rocket};
+
+use crate::person::PEOPLE;
+use crate::postprocess::validate;
+use crate::project_pages::project_page_routes;
+use crate::redirect::REDIRECTS;
+use crate::{archive, blog, pwa};
+
+// Everything which is exported to the static site (other than 'public/' and 'prestatic/', which 'gen_static.sh' copies as-is bar posts' Markdown, redirect stubs, and '404.html').
+// Pages are written out to '<route>/index.html', and files to '<route>'.
+
+pub fn pages() -> Vec<String> {
+	let mut pages = vec!["/".to_string(), "/projects".to_string(), "/articles".to_string()];
+
+	pages.extend(project_page_routes().iter().map(|route| route.uri.path().to_string()));
+	pages.extend(blog::published().flat_map(|blog| [blog.route.to_string(), blog.history_route()]));
+	pages.extend(PEOPLE.iter().map(|person| person.route()));
+	pages.push("/search".to_string());
+	pages.push("/archive".to_string());
+	pages.extend((1..=archive::page_count()).map(|n| format!("/articles/page/{}", n)));
+
+	pages
+}
+
+pub fn files() -> Vec<String> {
+	let mut files = vec![
+		"/manifest.webmanifest".to_string(),
+		"/sw.js".to_string(),
+		"/search.json".to_string(),
+	];
+	files.extend(pwa::FILES.iter().map(|(route, ..)| route.to_string()));
+
+	files
+}
+
+async fn write(client: &Client, route: &str, path: &Path, status: Status) -> Result<(), Box<dyn Error>> {
+	let response = client.get(route).dispatch().await;
+
+	if response.status() != status {
+		return Err(format!("{} returned {}", route, response.status()).into());
+	}
+
+	let html = response.content_type() == Some(ContentType::HTML);
+	let body = response.into_bytes().await.unwrap_or_default();
+
+	// The server only logs invalid HTML, as browsers recover from it anyway, but there's no excuse for publishing it.
+
+	if html {
+		let errors = validate(&String::from_utf8_lossy(&body));
+
+		if !errors.is_empty() {
+			return Err(format!("{} rendered invalid HTML:\n{}", route, errors.join("\n")).into());
+		}
+	}
+
+	fs::create_dir_all(path.parent().unwrap())?;
+	fs::write(path, body)?;
+
+	println!("{} -> {}", route, path.display());
+	Ok(())
+}
+
+pub async fn export(rocket: Rocket<Build>, dir: &Path) -> Result<(), Box<dyn Error>> {
+	let client = Client::tracked(rocket).await?;
+
+	for page in pages() {
+		write(
+			&client,
+			&page,
+			&dir.join(page.trim_start_matches('/')).join("index.html"),
+			Status::Ok,
+		)
+		.await?;
+	}
+
+	for file in files() {
+		write(&client, &file, &dir.join(file.trim_start_matches('/')), Status::Ok).await?;
+	}
+
+	for redirect in REDIRECTS {
+		let path = dir.join(redirect.from.trim_start_matches('/')).join("index.html");
+
+		fs::create_dir_all(path.parent().unwrap())?;
+		fs::write(&path, redirect.stub().into_string())?;
+
+		println!("{} -> {} (redirect to {})", redirect.from, path.display(), redirect.to);
+	}
+
+	write(&client, "/404.html", &dir.join("404.html"), Status::NotFound).await?;
+
+	Ok(())
+}
@@ -0,0 +1,38 @@
+use crate::social::SOURCE_CODE;
+
+pub struct Revision {
+	pub hash: &'static str,
+	pub date: &'static str,
+	pub summary: &'static str,
+}
+
+// Generated by 'build.rs' from the git history of 'public/blog/'.
+
+include!(concat!(env!("OUT_DIR"), "/history.rs"));
+
+const BRANCH: &str = "main";
+
+// Revisions of a file in 'public/', newest first.
+
+pub fn history(path: &str) -> &'static [Revision] {
+	HISTORY
+		.iter()
+		.find(|(revision_path, _)| *revision_path == path)
+		.map_or(&[], |(_, revisions)| revisions)
+}
+
+pub fn source_url(path: &str) -> String {
+	format!("{}/blob/{}/public{}", SOURCE_CODE.url, BRANCH, path)
+}
+
+pub fn edit_url(path: &str) -> String {
+	format!("{}/edit/{}/public{}", SOURCE_CODE.url, BRANCH, path)
+}
+
+pub fn history_url(path: &str) -> String {
+	format!("{}/commits/{}/public{}", SOURCE_CODE.url, BRANCH, path)
+}
+
+pub fn commit_url(revision: &Revision) -> String {
+	format!("{}/commit/{}", SOURCE_CODE.url, revision.hash)
+}
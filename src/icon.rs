@@ -0,0 +1,202 @@
+use maud::{html, Markup, PreEscaped, Render};
+
+use crate::common::str_eq;
+
+// Generated by 'build.rs' from 'public/icons'.
+
+include!(concat!(env!("OUT_DIR"), "/icons.rs"));
+
+#[derive(Clone, Copy)]
+pub enum Icon {
+	Arrow,
+	Back,
+	Bell,
+	Discord,
+	Email,
+	FreeBSD,
+	GitHub,
+	Instagram,
+	Link,
+	LinkedIn,
+	Magic,
+	YouTube,
+}
+
+impl Icon {
+	pub const ALL: &'static [Icon] = &[
+		Icon::Arrow,
+		Icon::Back,
+		Icon::Bell,
+		Icon::Discord,
+		Icon::Email,
+		Icon::FreeBSD,
+		Icon::GitHub,
+		Icon::Instagram,
+		Icon::Link,
+		Icon::LinkedIn,
+		Icon::Magic,
+		Icon::YouTube,
+	];
+
+	// Name of the file in 'public/icons', sans extension.
+
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Icon::Arrow => "arrow",
+			Icon::Back => "back",
+			Icon::Bell => "bell",
+			Icon::Discord => "discord",
+			Icon::Email => "email",
+			Icon::FreeBSD => "fbsd",
+			Icon::GitHub => "gh",
+			Icon::Instagram => "instagram",
+			Icon::Link => "link",
+			Icon::LinkedIn => "linkedin",
+			Icon::Magic => "magic",
+			Icon::YouTube => "youtube",
+		}
+	}
+
+	pub fn title(&self) -> &'static str {
+		match self {
+			Icon::Arrow => "Arrow",
+			Icon::Back => "Back",
+			Icon::Bell => "Webring",
+			Icon::Discord => "Discord",
+			Icon::Email => "Email",
+			Icon::FreeBSD => "FreeBSD",
+			Icon::GitHub => "GitHub",
+			Icon::Instagram => "Instagram",
+			Icon::Link => "External link",
+			Icon::LinkedIn => "LinkedIn",
+			Icon::Magic => "Interactive",
+			Icon::YouTube => "YouTube",
+		}
+	}
+
+	fn id(&self) -> String {
+		format!("icon-{}", self.name())
+	}
+
+	// (viewBox, minified contents)
+	// Dev builds read the icon from disk every time, so that editing it doesn't need a recompile.
+
+	#[cfg(not(feature = "dev"))]
+	fn icon(&self) -> (&'static str, &'static str) {
+		let (_, view_box, symbol) = ICONS
+			.iter()
+			.find(|(name, ..)| *name == self.name())
+			.unwrap_or_else(|| panic!("no icon named \"{}\" in public/icons", self.name()));
+
+		(view_box, symbol)
+	}
+
+	#[cfg(feature = "dev")]
+	fn icon(&self) -> (String, String) {
+		let path = format!("{}/{}.svg", crate::common::relative!("/public/icons"), self.name());
+		crate::svg::minify(&crate::dev::read(&path))
+	}
+}
+
+// Icons that 'main.css' uses as masks (for admonitions), rather than going through the sprite, so they need no variant.
+
+pub const CSS_ONLY: &[&str] = &["note", "tip", "warning"];
+
+// Check that the icons in 'public/icons' and the variants above match up, by name, at compile time.
+
+const fn has_variant(name: &str) -> bool {
+	let mut i = 0;
+
+	while i < CSS_ONLY.len() {
+		if str_eq(CSS_ONLY[i], name) {
+			return true;
+		}
+
+		i += 1;
+	}
+
+	let mut i = 0;
+
+	while i < Icon::ALL.len() {
+		if str_eq(Icon::ALL[i].name(), name) {
+			return true;
+		}
+
+		i += 1;
+	}
+
+	false
+}
+
+const fn has_svg(name: &str) -> bool {
+	let mut i = 0;
+
+	while i < ICONS.len() {
+		if str_eq(ICONS[i].0, name) {
+			return true;
+		}
+
+		i += 1;
+	}
+
+	false
+}
+
+const _: () = {
+	let mut i = 0;
+
+	while i < ICONS.len() {
+		assert!(
+			has_variant(ICONS[i].0),
+			"every SVG in public/icons must have a corresponding 'Icon' variant, or be in 'CSS_ONLY'"
+		);
+
+		i += 1;
+	}
+
+	let mut i = 0;
+
+	while i < Icon::ALL.len() {
+		assert!(
+			has_svg(Icon::ALL[i].name()),
+			"every 'Icon' variant must have an SVG in public/icons"
+		);
+		i += 1;
+	}
+};
+
+impl Render for Icon {
+	fn render(&self) -> Markup {
+		let (view_box, _) = self.icon();
+
+		html! {
+			svg role="img" viewBox=(view_box) {
+				title { (self.title()) }
+				use href=(format!("#{}", self.id())) {}
+			}
+		}
+	}
+}
+
+// Sprite containing all the icons used in 'content', for 'base()' to emit once per page.
+
+pub fn sprite(content: &str) -> Markup {
+	let used: Vec<&Icon> = Icon::ALL
+		.iter()
+		.filter(|icon| content.contains(&format!("\"#{}\"", icon.id())))
+		.collect();
+
+	html! {
+		@if !used.is_empty() {
+			svg.sprite aria-hidden="true" {
+				@for icon in used {
+					@let (view_box, symbol) = icon.icon();
+
+					symbol id=(icon.id()) viewBox=(view_box) {
+						(PreEscaped(symbol))
+					}
+				}
+			}
+		}
+	}
+}
@@ -1,30 +1,41 @@
 use maud::{html, Markup, PreEscaped};
 
-use crate::base::base;
-use crate::blog::BLOGS;
-use crate::common::{include_static, include_static_unsafe, relative};
-use crate::person::{person, Person};
-use crate::social::social;
+use crate::base::base_canonical;
+use crate::common::{include_static, include_static_unsafe};
+use crate::icon::Icon;
+use crate::person::{mentions, person, Person, ADITYA, ALESS, ALEX, ALEXIS, BRICHANT, DRAKEERV, JUK, NOA, PIWY};
+use crate::social::{ACCOUNTS, SOURCE_CODE};
+use crate::{blog, site};
 
-fn thing(title: &'static str, link: &'static str, magic: bool, img_src: &'static str, descr: Markup) -> Markup {
-	let alt: &str = &(title.to_owned() + " thumbnail");
+pub struct Project {
+	pub title: &'static str,
+	pub link: &'static str,
+	pub magic: bool,
+	pub img_src: &'static str,
+	pub tags: &'static [&'static str],
+	pub people: &'static [&'static Person], /* Everyone mentioned in its description or on its page (see 'person::mentions'). */
+	pub descr: fn() -> Markup,
+}
+
+pub fn thing(project: &Project) -> Markup {
+	let alt: &str = &(project.title.to_owned() + " thumbnail");
 
 	html! {
 		.thing {
 			.labeled-img {
-				img alt=(alt) src=(img_src);
+				img alt=(alt) src=(project.img_src);
 				div {
 					div {
-						h2 { (title) }
-						@if magic {
-							(include_static!("/icons/magic.svg"))
+						h2 { (project.title) }
+						@if project.magic {
+							(Icon::Magic)
 						}
 					}
 				}
 			}
-			p { (descr) }
-			a.learn-more href=(link) {
-				(include_static!("/icons/arrow.svg"))
+			p { ((project.descr)()) }
+			a.learn-more href=(project.link) {
+				(Icon::Arrow)
 				p { "Learn more" }
 			}
 		}
@@ -47,126 +58,288 @@ pub const _24H_VELO_IMG_SRC: &str = "/public/thumbnails/24hvelo-small.png";
 pub const DESIGN_IMG_SRC: &str = "/public/thumbnails/graphic-design-small.webp";
 pub const BATMAN_IMG_SRC: &str = "/public/thumbnails/batman-small.webp";
 
-fn projects() -> Markup {
-	html! {
-		.things {
-			(thing("aquaBSD", "https://github.com/inobulles/aquabsd/releases", false, AQUABSD_IMG_SRC, html! {
+pub const PROJECTS: &[&Project] = &[
+	&Project {
+		title: "aquaBSD",
+		link: "https://github.com/inobulles/aquabsd/releases",
+		magic: false,
+		img_src: AQUABSD_IMG_SRC,
+		tags: &["freebsd", "os", "desktop"],
+		people: &[],
+		descr: || {
+			html! {
 				"OS based on FreeBSD geared towards general users. Includes a full DE, app distribution system, and network device sharing."
-			}))
-
-			(thing("MCPY", "/mcpy", true, MCPY_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "MCPY",
+		link: "/mcpy",
+		magic: true,
+		img_src: MCPY_IMG_SRC,
+		tags: &["graphics", "opengl", "python", "tutorial"],
+		people: &[&DRAKEERV, &JUK],
+		descr: || {
+			html! {
 				"Video tutorial series on 3D graphics programming with OpenGL, where I write a Minecraft clone in Python."
-			}))
-
-			(thing("BFM", "/bfm", true, BFM_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "BFM",
+		link: "/bfm",
+		magic: true,
+		img_src: BFM_IMG_SRC,
+		tags: &["maths", "c", "python", "university"],
+		people: &[&ALEX],
+		descr: || {
+			html! {
 				"Big F'ing Matrix. FEM/FEA C library ("
 				code { "libbfm" }
 				") with Python bindings ("
 				code { "pybfm" }
 				") for use as an educational tool. "
-				(person(Person::Alex))
+				(person(&ALEX))
 				" and I made this for LEPL1110."
-			}))
-
-			(thing("KARWa", "/karwa", false, KARWA_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "KARWa",
+		link: "/karwa",
+		magic: false,
+		img_src: KARWA_IMG_SRC,
+		tags: &["algorithmics", "contest", "louvain-li-nux"],
+		people: &[&ALEX],
+		descr: || {
+			html! {
 				"Francophone algorithmics contest. Jointly organized by Louvain-li-Nux (in Louvain-la-Neuve) and CPUMons (in Mons)."
-			}))
-
-			(thing("B.A.T.M.A.N. on FreeBSD", "/batman", false, BATMAN_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "B.A.T.M.A.N. on FreeBSD",
+		link: "/batman",
+		magic: false,
+		img_src: BATMAN_IMG_SRC,
+		tags: &["freebsd", "networking", "kernel", "drivers"],
+		people: &[],
+		descr: || {
+			html! {
 				"Port of the B.A.T.M.A.N. mesh routing protocol to FreeBSD. Initially written as a GSoC project."
-			}))
-
-			(thing("24h Vélo", "/24hvelo", false, _24H_VELO_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "24h Vélo",
+		link: "/24hvelo",
+		magic: false,
+		img_src: _24H_VELO_IMG_SRC,
+		tags: &["graphics", "event"],
+		people: &[&ADITYA, &PIWY, &ALEXIS],
+		descr: || {
+			html! {
 				"Work done for the "
 				a.link href="https://24heureslln.be" { "24h Vélo de Louvain-la-Neuve" }
 				". Made a folkloric bike as well as visualization software for a giant screen on the Grand' Place."
-			}))
-
-			(thing("Graphic design", "/graphic-design", false, DESIGN_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "Graphic design",
+		link: "/graphic-design",
+		magic: false,
+		img_src: DESIGN_IMG_SRC,
+		tags: &["design", "event"],
+		people: &[],
+		descr: || {
+			html! {
 				"I like creating posters for various student events, and am generally (casually) interested in graphic design."
-			}))
-		}
-		p {
-			"Here are a few more random smaller side-projects I've worked on and that I deem to be finished."
-		}
-		.things {
-			(thing("Compositing WM", "/x-compositing-wm", false, X_IMG_SRC, html! {
+			}
+		},
+	},
+];
+
+// Smaller side-projects I deem to be finished.
+
+pub const SIDE_PROJECTS: &[&Project] = &[
+	&Project {
+		title: "Compositing WM",
+		link: "/x-compositing-wm",
+		magic: false,
+		img_src: X_IMG_SRC,
+		tags: &["graphics", "opengl", "x11", "c"],
+		people: &[&ADITYA],
+		descr: || {
+			html! {
 				"Extremely basic X11 compositing window manager written in C with Xlib and OpenGL. A modified version is used in a helicopter simulator at the "
 				a.link href="https://www.dlr.de/de/das-dlr/standorte-und-bueros/braunschweig" { "DLR in Braunschweig" }
 				"."
-			}))
-
-			(thing("MOOdle", "/moodle", true, MOODLE_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "MOOdle",
+		link: "/moodle",
+		magic: true,
+		img_src: MOODLE_IMG_SRC,
+		tags: &["graphics", "webgl", "web"],
+		people: &[&NOA, &ALEXIS],
+		descr: || {
+			html! {
 				"Advanced cow visualization tool, with a 3D pasture simulation written in WebGL. Made with "
-				(person(Person::Noa))
+				(person(&NOA))
 				" and "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				"."
-			}))
-
-			(thing("GDPR", "/gdpr", true, GDPR_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "GDPR",
+		link: "/gdpr",
+		magic: true,
+		img_src: GDPR_IMG_SRC,
+		tags: &["web", "presentation"],
+		people: &[&NOA, &BRICHANT],
+		descr: || {
+			html! {
 				"Interactive GDPR presentation "
-				(person(Person::Noa))
+				(person(&NOA))
 				" and I made in English class in highschool, which emulates a Windows 7 desktop."
-			}))
-
-			(thing("LLN '24", "https://github.com/obiwac/lln-gamejam-2024", false, LLN24_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "LLN '24",
+		link: "https://github.com/obiwac/lln-gamejam-2024",
+		magic: false,
+		img_src: LLN24_IMG_SRC,
+		tags: &["game", "graphics", "webgpu", "go", "louvain-li-nux"],
+		people: &[&PIWY, &ALEXIS],
+		descr: || {
+			html! {
 				"Submission for the 2024 Louvain-li-Nux gamejam. Written with "
-				(person(Person::Piwy))
+				(person(&PIWY))
 				" in Go with a custom WebGPU engine. You play a day in the life of "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				"."
-			}))
-
-			(thing("LLN '23", "https://github.com/obiwac/lln-gamejam-2023", false, LLN23_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "LLN '23",
+		link: "https://github.com/obiwac/lln-gamejam-2023",
+		magic: false,
+		img_src: LLN23_IMG_SRC,
+		tags: &["game", "graphics", "vulkan", "rust", "louvain-li-nux"],
+		people: &[&ALEXIS, &ALESS],
+		descr: || {
+			html! {
 				"Submission for the 2023 Louvain-li-Nux gamejam. aka "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				" and "
-				(person(Person::Aless))
+				(person(&ALESS))
 				" and I's first foray into Vulkan and Rust, aka Obamatriangle."
-			}))
-
-			(thing("LLN '22", "https://github.com/obiwac/lln-gamejam-2022", false, LLN22_IMG_SRC, html! {
+			}
+		},
+	},
+	&Project {
+		title: "LLN '22",
+		link: "https://github.com/obiwac/lln-gamejam-2022",
+		magic: false,
+		img_src: LLN22_IMG_SRC,
+		tags: &["game", "graphics", "x11", "c", "louvain-li-nux"],
+		people: &[&ALEXIS],
+		descr: || {
+			html! {
 				"Submission for the 2022 Louvain-li-Nux gamejam, made with "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				". Pure C11. Pure X11. Pure 7/11."
-			}))
+			}
+		},
+	},
+];
+
+fn projects() -> Markup {
+	html! {
+		.things {
+			@for project in PROJECTS {
+				(thing(project))
+			}
+		}
+		p {
+			"Here are a few more random smaller side-projects I've worked on and that I deem to be finished."
+		}
+		.things {
+			@for project in SIDE_PROJECTS {
+				(thing(project))
+			}
 		}
 	}
 }
 
+// Only the latest few, the rest are in the archive.
+
+const LATEST_ARTICLES: usize = 5;
+
 fn articles() -> Markup {
-	let entries: Vec<Markup> = BLOGS.iter().map(|blog| blog.render_entry()).collect();
+	let entries: Vec<Markup> = blog::latest()
+		.iter()
+		.take(LATEST_ARTICLES)
+		.map(|blog| blog.render_entry())
+		.collect();
 
 	html! {
 		@for entry in entries {
 			(entry)
 			hr;
 		}
+		p {
+			a.link href="/archive" { "See all articles" }
+		}
 	}
 }
 
-#[get("/")]
-pub fn index() -> Markup {
-	base(
-		"Aymeric Wibo",
-		"Personal website for Aymeric Wibo",
+#[derive(PartialEq)]
+enum Tab {
+	Projects,
+	Articles,
+}
+
+// Without a tab, neither is checked, so that '/#articles' can still pick that one (see 'main.css').
+// They're all the same page though, so '/' is the canonical one.
+
+fn render(tab: Option<Tab>) -> Markup {
+	let people = mentions(
+		PROJECTS
+			.iter()
+			.chain(SIDE_PROJECTS.iter())
+			.map(|project| project.people),
+		None,
+	);
+
+	base_canonical(
+		site::NAME,
+		site::DESCRIPTION,
 		include_static!("/schema/me.json"),
+		"/",
+		&people,
 		html! {
 			.page-container {
-				header role="banner" {
+				header.h-card role="banner" {
+					data.p-name value="Aymeric Wibo" {}
+					data.p-nickname value="obiwac" {}
+					data.u-photo value=(format!("{}/public{}", site::URL, site::ICON)) {}
+					data.u-url.u-uid value=(site::URL) {}
 					.section-container {
 						center {
 							h1 { "Hey! 👋" }
 						}
 						.socials {
-							(social("awibo", "https://www.linkedin.com/in/awibo", include_static!("/icons/linkedin.svg")))
-							(social("@obiwac", "https://github.com/obiwac", include_static!("/icons/gh.svg")))
-							(social("me@obiw.ac", "mailto:me@obiw.ac", include_static!("/icons/email.svg")))
-							(social("obiwac@freebsd.org", "mailto:obiwac@freebsd.org", include_static!("/icons/fbsd.svg")))
-							(social("obiwac", "https://youtube.com/obiwac", include_static!("/icons/youtube.svg")))
-							(social("obiwac", "https://discord.com/users/305047157197504522", include_static!("/icons/discord.svg")))
-							(social("Webring", "http://fuz.su", include_static!("/icons/bell.svg")))
+							@for account in ACCOUNTS {
+								(account.in_card())
+							}
 						}
 					}
 				}
@@ -179,7 +352,7 @@ pub fn index() -> Markup {
 							strong { "obiwac" }
 							"). I'm a Belgian open-source enthusiast who likes dogs and beer 🍺 Here are some of my bigger projects - those which have a "
 							span.inline-svg {
-								(include_static!("/icons/magic.svg"))
+								(Icon::Magic)
 							}
 							" next to their name are interactive experiences."
 						}
@@ -188,17 +361,17 @@ pub fn index() -> Markup {
 						}
 					}
 					.all-my-homies-hate-margin-collapsing {
-						input #projects-tab-input type="radio" name="tab" checked;
-						input #articles-tab-input type="radio" name="tab";
+						input #projects-tab-input type="radio" name="tab" aria-controls="projects" checked[tab == Some(Tab::Projects)];
+						input #articles-tab-input type="radio" name="tab" aria-controls="articles" checked[tab == Some(Tab::Articles)];
 						.tabs {
 							label #projects-tab-label for="projects-tab-input" { "Projects" }
 							label #articles-tab-label for="articles-tab-input" { "Articles" }
 						}
 						.tab-content {
-							#projects-tab .tab {
+							#projects .tab {
 								(projects())
 							}
-							#articles-tab .tab {
+							#articles .tab {
 								(articles())
 							}
 						}
@@ -211,10 +384,10 @@ pub fn index() -> Markup {
 							a.link href="https://rocket.rs" { "Rocket.rs" }
 							" and "
 							a.link href="https://maud.lambda.xyz" { "Maud" }
-							"! Fun fact: this site's source doesn't have a single line of the godforsaken language known as HTML in it. It does have some JS on some pages though (not this one), so count that as an L if you want."
+							"! Fun fact: this site's source doesn't have a single line of the godforsaken language known as HTML in it. It does have some JS on some pages though (on this one, only just enough to make it work offline), so count that as an L if you want."
 						}
 						.socials {
-							(social("Source code", "https://github.com/obiwac/obiwac.github.io", include_static!("/icons/gh.svg")))
+							(SOURCE_CODE)
 						}
 					}
 				}
@@ -222,3 +395,20 @@ pub fn index() -> Markup {
 		},
 	)
 }
+
+#[get("/")]
+pub fn index() -> Markup {
+	render(None)
+}
+
+// The same page with a tab already selected, so that each has a URL to share.
+
+#[get("/projects")]
+pub fn projects_tab() -> Markup {
+	render(Some(Tab::Projects))
+}
+
+#[get("/articles")]
+pub fn articles_tab() -> Markup {
+	render(Some(Tab::Articles))
+}
@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::error::Error;
+use std::fmt;
+
+use rocket::http::{Status, StatusClass};
+use rocket::local::asynchronous::Client;
+use rocket::{Build, Rocket};
+use scraper::{Html, Selector};
+
+use crate::export::pages;
+
+// Assets which are referenced by pages, but which aren't in the repo, until they're added (see "Known issues" in the README).
+// They're listed in the report rather than as broken links, and, once they do exist, have to be taken off this list.
+
+pub const KNOWN_MISSING: &[&str] = &["/public/bfm/index.html", "/public/karwa/promo.mp4"];
+
+const LINK_ATTRS: &[&str] = &["href", "src", "poster"];
+const SKIPPED_SCHEMES: &[&str] = &["mailto:", "tel:", "data:", "javascript:"];
+const MAX_REDIRECTS: usize = 8;
+
+#[derive(Default)]
+pub struct Report {
+	pub pages: usize,
+	pub internal: usize,
+	pub broken: Vec<(String, String, String)>,        // (page, link, reason)
+	pub missing: Vec<(String, String)>,               // (page, asset) of the links to 'KNOWN_MISSING' assets.
+	pub external: BTreeMap<String, BTreeSet<String>>, // Link to the pages it appears on.
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(
+			f,
+			"Checked {} internal links across {} pages.",
+			self.internal, self.pages
+		)?;
+
+		if !self.external.is_empty() {
+			writeln!(f, "\n{} external links (not fetched):", self.external.len())?;
+
+			for (link, pages) in &self.external {
+				let pages: Vec<&str> = pages.iter().map(String::as_str).collect();
+				writeln!(f, "\t{} ({})", link, pages.join(", "))?;
+			}
+		}
+
+		if !self.missing.is_empty() {
+			writeln!(f, "\n{} links to known missing assets:", self.missing.len())?;
+
+			for (page, link) in &self.missing {
+				writeln!(f, "\t{} on {}", link, page)?;
+			}
+		}
+
+		if !self.broken.is_empty() {
+			writeln!(f, "\n{} broken links:", self.broken.len())?;
+
+			for (page, link, reason) in &self.broken {
+				writeln!(f, "\t{} on {}: {}", link, page, reason)?;
+			}
+		}
+
+		Ok(())
+	}
+}
+
+fn split_fragment(link: &str) -> (&str, Option<&str>) {
+	match link.split_once('#') {
+		Some((path, fragment)) => (path, Some(fragment)),
+		None => (link, None),
+	}
+}
+
+// Make a link found on 'page' absolute, the same way a browser would.
+
+fn absolute(page: &str, link: &str) -> String {
+	let (path, fragment) = split_fragment(link);
+	let path = path.split('?').next().unwrap();
+
+	let path = match path {
+		"" => page.to_string(),
+		_ if path.starts_with('/') => path.to_string(),
+		_ => format!("{}{}", &page[..=page.rfind('/').unwrap()], path),
+	};
+
+	let path = match path.trim_end_matches('/') {
+		"" => "/".to_string(),
+		path => path.to_string(),
+	};
+
+	match fragment {
+		Some(fragment) => format!("{}#{}", path, fragment),
+		None => path,
+	}
+}
+
+// Fragments are only checked on pages, as on anything else (e.g. '#t=1' on videos) they don't refer to IDs.
+
+async fn resolve(client: &Client, ids: &HashMap<String, HashSet<String>>, link: &str) -> Result<(), String> {
+	let (path, fragment) = split_fragment(link);
+	let mut path = path.to_string();
+	let mut fragment = fragment.map(str::to_string);
+
+	for _ in 0..MAX_REDIRECTS {
+		if let Some(ids) = ids.get(&path) {
+			return match fragment {
+				Some(fragment) if !ids.contains(&fragment) => Err(format!("no element with ID \"{}\"", fragment)),
+				_ => Ok(()),
+			};
+		}
+
+		let response = client.get(path.clone()).dispatch().await;
+
+		if response.status() == Status::Ok {
+			return Ok(());
+		}
+
+		if response.status().class() != StatusClass::Redirection {
+			return Err(response.status().to_string());
+		}
+
+		let location = response
+			.headers()
+			.get_one("Location")
+			.ok_or("redirect without a location")?;
+
+		if !location.starts_with('/') {
+			return Ok(()); // Redirects to external sites aren't followed, just like external links.
+		}
+
+		let (location_path, location_fragment) = split_fragment(location);
+
+		path = location_path.to_string();
+		fragment = location_fragment.map(str::to_string).or(fragment);
+	}
+
+	Err("too many redirects".to_string())
+}
+
+// Render every page, and check that every internal link on it points to something which exists.
+// External links are collected in the report, but never fetched, so this works offline.
+
+pub async fn check(client: &Client) -> Report {
+	let mut report = Report::default();
+	let mut ids: HashMap<String, HashSet<String>> = HashMap::new();
+	let mut links: Vec<(String, String)> = Vec::new();
+
+	let id_selector = Selector::parse("[id]").unwrap();
+	let link_selector = Selector::parse("[href], [src], [poster]").unwrap();
+
+	for page in pages() {
+		let html = client
+			.get(page.clone())
+			.dispatch()
+			.await
+			.into_string()
+			.await
+			.unwrap_or_default();
+		let document = Html::parse_document(&html);
+
+		let page_ids = document
+			.select(&id_selector)
+			.filter_map(|element| element.value().id())
+			.map(str::to_string)
+			.collect();
+
+		ids.insert(page.clone(), page_ids);
+
+		for element in document.select(&link_selector) {
+			for attr in LINK_ATTRS {
+				if let Some(link) = element.value().attr(attr) {
+					links.push((page.clone(), link.to_string()));
+				}
+			}
+		}
+
+		report.pages += 1;
+	}
+
+	let mut resolved: HashMap<String, Result<(), String>> = HashMap::new();
+
+	for (page, link) in links {
+		if SKIPPED_SCHEMES.iter().any(|scheme| link.starts_with(scheme)) {
+			continue;
+		}
+
+		if link.starts_with("http://") || link.starts_with("https://") || link.starts_with("//") {
+			report.external.entry(link).or_default().insert(page);
+			continue;
+		}
+
+		let target = absolute(&page, &link);
+		report.internal += 1;
+
+		let known_missing = KNOWN_MISSING.contains(&split_fragment(&target).0);
+
+		if !resolved.contains_key(&target) {
+			let result = resolve(client, &ids, &target).await;
+			resolved.insert(target.clone(), result);
+		}
+
+		match (&resolved[&target], known_missing) {
+			(Ok(()), true) => report
+				.broken
+				.push((page, link, "exists, so take it off 'KNOWN_MISSING'".to_string())),
+			(Ok(()), false) => {}
+			(Err(_), true) => report.missing.push((page, target.clone())),
+			(Err(reason), false) => report.broken.push((page, link, reason.clone())),
+		}
+	}
+
+	report
+}
+
+pub async fn check_links(rocket: Rocket<Build>) -> Result<(), Box<dyn Error>> {
+	let client = Client::tracked(rocket).await?;
+	let report = check(&client).await;
+
+	print!("{}", report);
+
+	if !report.broken.is_empty() {
+		return Err(format!("found {} broken links", report.broken.len()).into());
+	}
+
+	Ok(())
+}
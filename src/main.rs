@@ -9,28 +9,102 @@ extern crate css_minify;
 extern crate maud;
 extern crate pulldown_cmark;
 
+use std::error::Error;
+use std::path::Path;
+
 use blog::blog_routes;
 use common::relative;
 use project_pages::project_page_routes;
+use pwa::pwa_routes;
+use redirect::redirect_routes;
+use rocket::fairing::AdHoc;
 use rocket::fs::FileServer;
+use rocket::{Build, Rocket};
 
+#[cfg(test)]
+mod a11y;
+mod archive;
+mod asset;
 mod base;
 mod blog;
+mod catchers;
 mod common;
+mod config;
+#[cfg(feature = "dev")]
+mod dev;
+mod embed;
+mod export;
+mod history;
+mod icon;
 mod index;
+mod links;
 mod person;
+mod postprocess;
 mod project_pages;
+mod pwa;
+mod redirect;
+mod related;
+mod search;
+mod security;
+mod site;
 mod social;
+#[cfg(feature = "dev")]
+mod svg;
+mod sw;
+
+#[cfg(test)]
+mod tests;
 
 // server stuff
 
-#[launch]
-fn rocket() -> _ {
+fn rocket() -> Rocket<Build> {
 	let rocket = rocket::build();
 
+	#[cfg(feature = "dev")]
+	let rocket = rocket.mount("/", routes![dev::livereload]).attach(dev::live_reload());
+
 	rocket
-		.mount("/", routes![index::index])
+		.mount("/", routes![
+			index::index,
+			index::projects_tab,
+			index::articles_tab,
+			person::people,
+			sw::sw,
+			blog::preview,
+			blog::markdown_source,
+			search::search,
+			search::search_index,
+			archive::archive,
+			archive::articles_page,
+			catchers::static_not_found
+		])
 		.mount("/", project_page_routes())
 		.mount("/", blog_routes())
+		.mount("/", pwa_routes())
+		.mount("/", redirect_routes())
 		.mount("/public", FileServer::from(relative!("/public")))
+		.register("/", catchers![catchers::not_found, catchers::internal_error])
+		.attach(AdHoc::config::<config::Config>())
+		.attach(blog::preview_links())
+		.attach(related::related())
+		.attach(search::index())
+		.attach(postprocess::PostProcess)
+		.attach(security::shield())
+		.attach(security::ContentSecurityPolicy)
+}
+
+// Run the webserver, 'export <dir>' to export a static site, or 'check-links' to check internal links.
+
+#[rocket::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+	let args: Vec<String> = std::env::args().collect();
+
+	match args.get(1).map(String::as_str) {
+		Some("export") => export::export(rocket(), Path::new(args.get(2).map_or("static", String::as_str))).await,
+		Some("check-links") => links::check_links(rocket()).await,
+		_ => {
+			rocket().launch().await?;
+			Ok(())
+		}
+	}
 }
@@ -1,29 +1,222 @@
-use maud::{html, Markup};
-
-pub enum Person {
-	Noa,
-	Alexis,
-	Alex,
-	Drakeerv,
-	Juk,
-	Brichant,
-	Aless,
-	Piwy,
-	Aditya,
+use maud::{html, Markup, PreEscaped};
+use serde_json::{json, Value};
+
+use crate::base::base;
+use crate::blog;
+use crate::icon::Icon;
+use crate::index::{thing, PROJECTS, SIDE_PROJECTS};
+
+pub struct Person {
+	pub handle: &'static str, // Used in the '/people/<handle>' route.
+	pub name: &'static str,
+	pub url: &'static str,
+	pub profiles: &'static [&'static str],
+}
+
+impl Person {
+	pub fn route(&self) -> String {
+		format!("/people/{}", self.handle)
+	}
+
+	fn is_in(&self, people: &[&Person]) -> bool {
+		people.iter().any(|person| person.handle == self.handle)
+	}
+
+	fn json(&self) -> Value {
+		json!({
+			"@type": "Person",
+			"name": self.name,
+			"url": self.url,
+			"sameAs": self.profiles,
+		})
+	}
+
+	pub fn schema(&self) -> String {
+		let mut json = self.json();
+		json["@context"] = "http://schema.org".into();
+		script_json(&json)
+	}
+
+	fn render(&self) -> Markup {
+		let projects: Vec<_> = PROJECTS
+			.iter()
+			.chain(SIDE_PROJECTS.iter())
+			.filter(|project| self.is_in(project.people))
+			.collect();
+
+		let blogs: Vec<_> = blog::published().filter(|blog| self.is_in(blog.people)).collect();
+
+		let description = format!("Projects and articles {} has been a part of", self.name);
+		let schema = self.schema();
+
+		// Whoever they worked with is mentioned there too, but they are the page's subject rather than a mention.
+
+		let people = mentions(
+			projects
+				.iter()
+				.map(|project| project.people)
+				.chain(blogs.iter().map(|blog| blog.people)),
+			Some(self),
+		);
+
+		base(self.name, &description, PreEscaped(&schema), &people, html! {
+			a.go-back href="/" {
+				(Icon::Back)
+				p { "Main page" }
+			}
+			main.blog-container {
+				.h-card {
+					h1.blog-title.p-name { (self.name) }
+					p {
+						a.link.u-url href=(self.url) { (self.url) }
+					}
+					@for profile in self.profiles {
+						@if *profile != self.url {
+							p {
+								a.link.u-url href=(profile) { (profile) }
+							}
+						}
+					}
+				}
+				@if !projects.is_empty() {
+					hr;
+					h2 { "Projects" }
+					.things {
+						@for project in projects {
+							(thing(project))
+						}
+					}
+				}
+				@if !blogs.is_empty() {
+					hr;
+					h2 { "Articles" }
+					@for blog in blogs {
+						(blog.render_entry())
+					}
+				}
+			}
+		})
+	}
 }
 
-pub fn person(person: Person) -> Markup {
+pub const NOA: Person = Person {
+	handle: "noa",
+	name: "Noa",
+	url: "https://novation.dev",
+	profiles: &["https://novation.dev", "https://github.com/novati0n"],
+};
+
+pub const ALEXIS: Person = Person {
+	handle: "alexis",
+	name: "Alexis",
+	url: "https://github.com/alexisenglebert",
+	profiles: &["https://github.com/alexisenglebert"],
+};
+
+pub const ALEX: Person = Person {
+	handle: "alex",
+	name: "Alex",
+	url: "https://github.com/alleyezoncode",
+	profiles: &["https://github.com/alleyezoncode"],
+};
+
+pub const DRAKEERV: Person = Person {
+	handle: "drakeerv",
+	name: "@drakeerv",
+	url: "https://github.com/drakeerv",
+	profiles: &["https://github.com/drakeerv", "https://drakeerv.github.io"],
+};
+
+pub const JUK: Person = Person {
+	handle: "jukitsu",
+	name: "@jukitsu",
+	url: "https://github.com/jukitsu",
+	profiles: &["https://github.com/jukitsu"],
+};
+
+pub const BRICHANT: Person = Person {
+	handle: "brichant",
+	name: "Monsieur Brichant",
+	url: "http://brichant.eu",
+	profiles: &["http://brichant.eu"],
+};
+
+pub const ALESS: Person = Person {
+	handle: "aless",
+	name: "Aless",
+	url: "https://github.com/akialess",
+	profiles: &["https://github.com/akialess"],
+};
+
+pub const PIWY: Person = Person {
+	handle: "piwy",
+	name: "Piwy",
+	url: "https://github.com/Piwy-dev",
+	profiles: &["https://github.com/Piwy-dev"],
+};
+
+pub const ADITYA: Person = Person {
+	handle: "aditya",
+	name: "Aditya",
+	url: "https://adityachugh.be",
+	profiles: &["https://adityachugh.be"],
+};
+
+pub const SUPERM1: Person = Person {
+	handle: "superm1",
+	name: "Mario Limonciello",
+	url: "https://github.com/superm1",
+	profiles: &["https://github.com/superm1"],
+};
+
+pub const PEOPLE: &[&Person] = &[
+	&NOA, &ALEXIS, &ALEX, &DRAKEERV, &JUK, &BRICHANT, &ALESS, &PIWY, &ADITYA, &SUPERM1,
+];
+
+pub fn person(person: &Person) -> Markup {
 	html! {
-		@match person {
-			Person::Noa => a.link href="https://novation.dev" { "Noa" },
-			Person::Alexis => a.link href="https://github.com/alexisenglebert" { "Alexis" },
-			Person::Alex => a.link href="https://github.com/alleyezoncode" { "Alex" },
-			Person::Drakeerv => a.link href="https://github.com/drakeerv" { "@drakeerv" },
-			Person::Juk => a.link href="https://github.com/jukitsu" { "@jukitsu" },
-			Person::Brichant => a.link href="http://brichant.eu" { "Monsieur Brichant" },
-			Person::Aless => a.link href="https://github.com/akialess" { "Aless" },
-			Person::Piwy => a.link href="https://github.com/Piwy-dev" { "Piwy" },
-			Person::Aditya => a.link href="https://adityachugh.be" { "Aditya" },
+		a.link href=(person.route()) { (person.name) }
+	}
+}
+
+// JSON-LD goes in a script element, so make sure nothing in it can close that early.
+
+fn script_json(json: &Value) -> String {
+	json.to_string().replace("</", "<\\/")
+}
+
+// Everyone in some projects' and posts' 'people', in order and only once, other than whoever the page is about ('subject').
+
+pub fn mentions<'a>(
+	people: impl IntoIterator<Item = &'a [&'static Person]>,
+	subject: Option<&Person>,
+) -> Vec<&'static Person> {
+	let mut mentioned: Vec<&'static Person> = Vec::new();
+
+	for &person in people.into_iter().flatten() {
+		if subject.is_none_or(|subject| subject.handle != person.handle) && !person.is_in(&mentioned) {
+			mentioned.push(person);
 		}
 	}
+
+	mentioned
+}
+
+// One schema block for everyone mentioned on a page (see 'base').
+
+pub fn mentions_schema(people: &[&Person]) -> String {
+	let graph: Vec<Value> = people.iter().map(|person| person.json()).collect();
+
+	script_json(&json!({
+		"@context": "http://schema.org",
+		"@graph": graph,
+	}))
+}
+
+#[get("/people/<handle>")]
+pub fn people(handle: &str) -> Option<Markup> {
+	PEOPLE
+		.iter()
+		.find(|person| person.handle == handle)
+		.map(|person| person.render())
 }
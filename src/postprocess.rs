@@ -0,0 +1,84 @@
+use std::collections::HashSet;
+use std::io::Cursor;
+
+use minify_html::Cfg;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::ContentType;
+use rocket::{Request, Response};
+use scraper::{Html, Selector};
+
+use crate::config::Config;
+
+// Structural errors (unclosed or misnested elements, duplicate IDs, &c).
+// Browsers recover from these, but never in quite the same way, so we'd rather know about them.
+
+pub fn validate(html: &str) -> Vec<String> {
+	let document = Html::parse_document(html);
+	let mut errors: Vec<String> = document.errors.iter().map(|error| error.to_string()).collect();
+	let mut ids = HashSet::new();
+
+	for element in document.select(&Selector::parse("[id]").unwrap()) {
+		let id = element.value().id().unwrap();
+
+		if !ids.insert(id) {
+			errors.push(format!("duplicate ID \"{}\"", id));
+		}
+	}
+
+	errors
+}
+
+// Inline scripts and styles are left alone, as the CSP allows them by the hash of their exact contents (and 'main.css' is already minified anyway).
+
+pub fn minify(html: &str) -> String {
+	let cfg = Cfg {
+		keep_html_and_head_opening_tags: true,
+		minify_css: false,
+		minify_js: false,
+		..Cfg::spec_compliant()
+	};
+
+	String::from_utf8(minify_html::minify(html.as_bytes(), &cfg)).unwrap()
+}
+
+// Validates, and then optionally minifies, every page we render.
+// Invalid pages are still served as they are, as browsers recover from these errors anyway; they're only logged here, and it's the tests and the static export which fail on them.
+// Files in 'public/' are served as-is.
+
+pub struct PostProcess;
+
+#[rocket::async_trait]
+impl Fairing for PostProcess {
+	fn info(&self) -> Info {
+		Info {
+			name: "HTML post-processing",
+			kind: Kind::Response,
+		}
+	}
+
+	async fn on_response<'r>(&self, req: &'r Request<'_>, res: &mut Response<'r>) {
+		if res.content_type() != Some(ContentType::HTML) || req.uri().path().starts_with("/public/") {
+			return;
+		}
+
+		let Ok(mut html) = res.body_mut().to_string().await else {
+			return;
+		};
+
+		let errors = validate(&html);
+
+		if !errors.is_empty() {
+			warn!("{} rendered invalid HTML:", req.uri());
+
+			for error in &errors {
+				warn_!("{}", error);
+			}
+		}
+
+		if req.rocket().state::<Config>().is_some_and(|config| config.minify_html) {
+			html = minify(&html);
+		}
+
+		res.set_sized_body(html.len(), Cursor::new(html));
+	}
+}
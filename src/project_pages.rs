@@ -1,14 +1,55 @@
 use maud::{html, Markup, PreEscaped};
+use rocket::route::Outcome;
 
+use crate::asset::asset;
 use crate::base::base;
-use crate::common::{include_static, include_static_unsafe, relative};
+use crate::common::{include_static, include_static_unsafe};
+use crate::embed::{embed, Embed};
+use crate::icon::Icon;
 use crate::index::{
-	BATMAN_IMG_SRC, BFM_IMG_SRC, DESIGN_IMG_SRC, GDPR_IMG_SRC, KARWA_IMG_SRC, MCPY_IMG_SRC, MOODLE_IMG_SRC, X_IMG_SRC,
-	_24H_VELO_IMG_SRC,
+	BATMAN_IMG_SRC, BFM_IMG_SRC, DESIGN_IMG_SRC, GDPR_IMG_SRC, KARWA_IMG_SRC, MCPY_IMG_SRC, MOODLE_IMG_SRC, PROJECTS,
+	SIDE_PROJECTS, X_IMG_SRC, _24H_VELO_IMG_SRC,
 };
-use crate::person::{person, Person};
+use crate::person::{person, ADITYA, ALEX, ALEXIS, BRICHANT, DRAKEERV, JUK, NOA, PIWY};
 use crate::social::social;
 
+// Inline scripts and third-party embeds, which the CSP allows on the pages they're on (see 'security::content_security_policy').
+// Anything inline or embedded which isn't listed here is blocked!
+
+const MOODLE_SETTINGS: &str = r#"
+	var invert_gravity = false
+	var cow_speed = 2
+
+	var data = {
+		"Holstein": 20,
+		"Jersey": 5,
+		"Blanc Bleu Belge": 10,
+	}
+"#
+.trim_ascii(); // Minifying the page would otherwise trim it, and then it wouldn't match its hash anymore.
+
+pub const INLINE_SCRIPTS: &[&str] = &[MOODLE_SETTINGS];
+
+const MCPY_DEMO: Embed = Embed {
+	title: "Drakeerv's port of MCPY to the browser",
+	src: "https://drakeerv.github.io/js-minecraft-clone/episodes/episode-11/index.html",
+	poster: "/public/thumbnails/mcpy.png",
+};
+
+const GDPR_PRESENTATION: Embed = Embed {
+	title: "The GDPR presentation in question",
+	src: "https://novation.dev/GDPR-presentation",
+	poster: "/public/thumbnails/gdpr.png",
+};
+
+const BATMAN_TALK: Embed = Embed {
+	title: "Calling the BATMAN: Free Networks on FreeBSD By: Aymeric Wibo",
+	src: "https://www.youtube-nocookie.com/embed/BAVogweBQ8M?list=PLeF8ZihVdpFfct_WnzwObWtj4y9qH3H7X",
+	poster: BATMAN_IMG_SRC,
+};
+
+pub const EMBEDS: &[&Embed] = &[&MCPY_DEMO, &GDPR_PRESENTATION, &BATMAN_TALK];
+
 fn explanation_page(title: &'static str, img_src: &'static str, descr: Markup, exhibit: Markup) -> Markup {
 	let schema = format!(
 		r#"{{
@@ -26,9 +67,17 @@ fn explanation_page(title: &'static str, img_src: &'static str, descr: Markup, e
 
 	let description = format!("Project explanation page for \"{}\"", title);
 
-	base(title, &description, PreEscaped(&schema), html! {
+	// Whoever's mentioned is listed on its project, which is the one with the same thumbnail.
+
+	let people = PROJECTS
+		.iter()
+		.chain(SIDE_PROJECTS.iter())
+		.find(|project| project.img_src == img_src)
+		.map_or(&[][..], |project| project.people);
+
+	base(title, &description, PreEscaped(&schema), people, html! {
 		a.go-back href="/" {
-			(include_static!("/icons/back.svg"))
+			(Icon::Back)
 			p { "Main page" }
 		}
 		.explanation-container {
@@ -47,7 +96,6 @@ fn explanation_page(title: &'static str, img_src: &'static str, descr: Markup, e
 	})
 }
 
-#[get("/mcpy")]
 pub fn mcpy() -> Markup {
 	explanation_page(
 		"MCPY ⛏️",
@@ -56,31 +104,30 @@ pub fn mcpy() -> Markup {
 			p { "Video tutorial series on 3D graphics programming, where I write a Minecraft clone in Python." }
 			p {
 				"This page has an interactive demo (of episode 11) made in WebGL based on MCPY by "
-				(person(Person::Drakeerv))
+				(person(&DRAKEERV))
 				" - it takes a little while to load (because JS is slow), but once it's loaded, you can click on it and move around like the real thing!"
 			}
 			p {
 				"The "
 				code { "community/" }
 				" directory on the GitHub repo (mostly maintained by "
-				(person(Person::Juk))
+				(person(&JUK))
 				" and "
-				(person(Person::Drakeerv))
+				(person(&DRAKEERV))
 				") implements other cool features, such as lighting, smooth shading, and (soon) mobs!"
 			}
 			.socials {
-				(social("Playlist", "https://www.youtube.com/watch?v=fWkbIOna6RA&list=PL6_bLxRDFzoKjaa3qCGkwR5L_ouSreaVP", include_static!("/icons/youtube.svg")))
-				(social("Source code", "https://github.com/obiwac/python-minecraft-clone", include_static!("/icons/gh.svg")))
-				(social("Full demo", "https://drakeerv.github.io/js-minecraft-clone/", include_static!("/icons/link.svg")))
+				(social("Playlist", "https://www.youtube.com/watch?v=fWkbIOna6RA&list=PL6_bLxRDFzoKjaa3qCGkwR5L_ouSreaVP", Icon::YouTube))
+				(social("Source code", "https://github.com/obiwac/python-minecraft-clone", Icon::GitHub))
+				(social("Full demo", "https://drakeerv.github.io/js-minecraft-clone/", Icon::Link))
 			}
 		},
 		html! {
-			iframe title="Drakeerv's port of MCPY to the browser" src="https://drakeerv.github.io/js-minecraft-clone/episodes/episode-11/index.html" loading="lazy" {}
+			(embed!(MCPY_DEMO))
 		},
 	)
 }
 
-#[get("/bfm")]
 pub fn bfm() -> Markup {
 	explanation_page(
 		"Big F'ing Matrix 🌉",
@@ -88,7 +135,7 @@ pub fn bfm() -> Markup {
 		html! {
 			p {
 				"BFM (aka. Big F***ing Matrix) is a FEM/FEA C library with Python bindings and 3D visualization tool. I wrote this with "
-				(person(Person::Alex))
+				(person(&ALEX))
 				" as our final project for the "
 				a.link href="https://perso.uclouvain.be/vincent.legat/zouLab/epl1110.php" { "LEPL1110" }
 				" course at uni."
@@ -102,7 +149,7 @@ pub fn bfm() -> Markup {
 				"I have plans to extend this more and use it as an educational tool (complemented by video tutorials). Stay tuned!!"
 			}
 			.socials {
-				(social("Source code", "https://github.com/obiwac/bfm", include_static!("/icons/gh.svg")))
+				(social("Source code", "https://github.com/obiwac/bfm", Icon::GitHub))
 			}
 		},
 		html! {
@@ -111,7 +158,6 @@ pub fn bfm() -> Markup {
 	)
 }
 
-#[get("/moodle")]
 pub fn moodle() -> Markup {
 	explanation_page(
 		"MOOdle 🐮",
@@ -119,9 +165,9 @@ pub fn moodle() -> Markup {
 		html! {
 			p {
 				"Advanced cow visualization tool. This was originally made with my friends "
-				(person(Person::Noa))
+				(person(&NOA))
 				" and "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				" for a university course, using our proprietary VirtualRanch™ technology."
 			}
 			p {
@@ -135,25 +181,14 @@ pub fn moodle() -> Markup {
 				": French. Sensitive viewers are advised to look away."
 			}
 			.socials {
-				(social("Source code", "https://github.com/novati0n/moodle", include_static!("/icons/gh.svg")))
-				(social("Full version", "https://moodle.novation.dev", include_static!("/icons/link.svg")))
+				(social("Source code", "https://github.com/novati0n/moodle", Icon::GitHub))
+				(social("Full version", "https://moodle.novation.dev", Icon::Link))
 			}
 		},
 		html! {
 			// settings (because we're not attached to a full webapp anymore)
 
-			script {
-				(PreEscaped(r#"
-				var invert_gravity = false
-				var cow_speed = 2
-
-				var data = {
-					"Holstein": 20,
-					"Jersey": 5,
-					"Blanc Bleu Belge": 10,
-				}
-			"#))
-			}
+			script { (PreEscaped(MOODLE_SETTINGS)) }
 
 			// shaders
 
@@ -162,20 +197,21 @@ pub fn moodle() -> Markup {
 
 			// models
 
-			script src="/public/moodle/models/paturage.js" defer {}
-			script src="/public/moodle/models/holstein.js" defer {}
-			script src="/public/moodle/models/jersey.js" defer {}
-			script src="/public/moodle/models/bbb.js" defer {}
+			(asset!("/moodle/models/paturage.js"))
+			(asset!("/moodle/models/holstein.js"))
+			(asset!("/moodle/models/jersey.js"))
+			(asset!("/moodle/models/bbb.js"))
 
 			// actual paturage
 
-			canvas #paturage title="A herd of cows having the time of their lives... in captivity" width="800px" height="500px" onclick="paturage.click()" {}
-			script src="/public/moodle/paturage.js" defer {}
+			canvas #paturage title="A herd of cows having the time of their lives... in captivity" width="800px" height="500px" {
+				"A herd of cows having the time of their lives... in captivity. Click to make them jump!"
+			}
+			(asset!("/moodle/paturage.js"))
 		},
 	)
 }
 
-#[get("/gdpr")]
 pub fn gdpr() -> Markup {
 	explanation_page(
 		"GDPR 🤓",
@@ -183,7 +219,7 @@ pub fn gdpr() -> Markup {
 		html! {
 			p {
 				"Interactive (try it out right here - don't worry, we don't use cookies 😉) GDPR presentation my friend "
-				(person(Person::Noa))
+				(person(&NOA))
 				" and I made in English class in highschool. As such, some parts may be written in French, as this was an English class in "
 				a.link href="https://en.wikipedia.org/wiki/Wallonia" { "Wallonia" }
 				" 🇧🇪"
@@ -192,23 +228,22 @@ pub fn gdpr() -> Markup {
 			p { "Also, the code is very not pretty. We wrote this in like 2 days, certainly not with the intention of further maintaining it." }
 			p {
 				"In memorandum "
-				(person(Person::Brichant))
+				(person(&BRICHANT))
 				" (don't press "
 				code { "Ctrl+Alt+B" }
 				") ❤️"
 			}
 			.socials {
-				(social("Source code", "https://github.com/novati0n/gdpr-presentation", include_static!("//icons/gh.svg")))
-				(social("Full version", "https://novation.dev/GDPR-presentation", include_static!("/icons/link.svg")))
+				(social("Source code", "https://github.com/novati0n/gdpr-presentation", Icon::GitHub))
+				(social("Full version", "https://novation.dev/GDPR-presentation", Icon::Link))
 			}
 		},
 		html! {
-			iframe title="The GDPR presentation in question" src="https://novation.dev/GDPR-presentation" loading="lazy" {}
+			(embed!(GDPR_PRESENTATION))
 		},
 	)
 }
 
-#[get("/karwa")]
 pub fn karwa() -> Markup {
 	explanation_page(
 		"KARWa 🧮",
@@ -216,7 +251,7 @@ pub fn karwa() -> Markup {
 		html! {
 			p {
 				"Francophone algorithmics contest, standing for \"Kompétition d'Algorithmique Régionale Wallonne\". Jointly organized by Louvain-li-Nux (in Louvain-la-Neuve) and CPUMons (in Mons). Created in 2022 by "
-				(person(Person::Alex))
+				(person(&ALEX))
 				" and I after happening to be in the same train as the Mons team on the way back from the "
 				a.link href="https://nwerc.eu/" { "NWERC" }
 				" algorithmics contest in Delft. The name was inspired by a legendary karaoke we had in Eindhoven a month prior."
@@ -225,9 +260,9 @@ pub fn karwa() -> Markup {
 			p { "It was successful enough to organize again in 2024, and had similar modalities." }
 			p { "To the right is a promotional visual I made for the 2024 edition which was played on the screens in the halls of the engineering faculty." }
 			.socials {
-				(social("KARWa '23", "https://github.com/karwa-org/karwa2023", include_static!("//icons/gh.svg")))
-				(social("KARWa '24", "https://github.com/karwa-org/karwa2024", include_static!("//icons/gh.svg")))
-				(social("Website", "https://alexisenglebert.github.io/", include_static!("/icons/link.svg")))
+				(social("KARWa '23", "https://github.com/karwa-org/karwa2023", Icon::GitHub))
+				(social("KARWa '24", "https://github.com/karwa-org/karwa2024", Icon::GitHub))
+				(social("Website", "https://alexisenglebert.github.io/", Icon::Link))
 			}
 		},
 		html! {
@@ -239,7 +274,6 @@ pub fn karwa() -> Markup {
 	)
 }
 
-#[get("/graphic-design")]
 pub fn graphic_design() -> Markup {
 	explanation_page(
 		"Graphic design 🎨",
@@ -262,8 +296,8 @@ pub fn graphic_design() -> Markup {
 				"Some of them have animated equivalents which are displayed on our social media profiles."
 			}
 			.socials {
-				(social("@louvainlinux", "https://instagram.com/louvainlinux", include_static!("//icons/instagram.svg")))
-				(social("Website", "https://louvainlinux.org", include_static!("//icons/link.svg")))
+				(social("@louvainlinux", "https://instagram.com/louvainlinux", Icon::Instagram))
+				(social("Website", "https://louvainlinux.org", Icon::Link))
 			}
 		},
 		html! {
@@ -284,7 +318,6 @@ pub fn graphic_design() -> Markup {
 	)
 }
 
-#[get("/x-compositing-wm")]
 pub fn x_compositing_wm() -> Markup {
 	explanation_page(
 		"X Compositing WM 🪟",
@@ -299,14 +332,14 @@ pub fn x_compositing_wm() -> Markup {
 				"I got to visit the "
 				a.link href="https://www.dlr.de/de/das-dlr/standorte-und-bueros/braunschweig" { "DLR in Braunschweig" }
 				" who based a WM on this one to project onto a large spherical screen for use in a helicopter simulator on this code. On the right is a photo of my friend "
-				(person(Person::Aditya))
+				(person(&ADITYA))
 				" attempting to fly it."
 			}
 			p {
 				"Braunschweig and Hanover, which we also stayed at, are both very nice cities. I recommend visiting them if you're in the area."
 			}
 			.socials {
-				(social("Source code", "https://github.com/obiwac/x-compositing-wm", include_static!("//icons/gh.svg")))
+				(social("Source code", "https://github.com/obiwac/x-compositing-wm", Icon::GitHub))
 			}
 		},
 		html! {
@@ -315,7 +348,6 @@ pub fn x_compositing_wm() -> Markup {
 	)
 }
 
-#[get("/24hvelo")]
 pub fn _24hvelo() -> Markup {
 	explanation_page(
 		"24h Vélo 🚲",
@@ -325,17 +357,17 @@ pub fn _24hvelo() -> Markup {
 				"During the "
 				a.link href="https://24heureslln.be" { "24h Vélo de Louvain-la-Neuve" }
 				", I built a folkloric bike with "
-				(person(Person::Aditya))
+				(person(&ADITYA))
 				", "
-				(person(Person::Piwy))
+				(person(&PIWY))
 				", and "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				" for my "
 				a.link href="https://en.wikipedia.org/wiki/Theme-based_shared_flat_(kot-%C3%A0-projet)" { "KAP" }
 				" ("
 				a.link href="https://louvainlinux.org" { "Louvain-li-Nux" }
 				") and I wrote visualization software with "
-				(person(Person::Alexis))
+				(person(&ALEXIS))
 				" for a giant 250K EUR screen on the Grand' Place (where the biggest of the 7 concurrent concerts take place). Once they were all done, I played "
 				a.link href="https://supertuxkart.net/Main_Page" { "SuperTuxKart" }
 				" on it, which is certainly the most expensive gaming monitor I've ever played on."
@@ -350,7 +382,7 @@ pub fn _24hvelo() -> Markup {
 				"I'd also like to host the visualization software itself here."
 			}
 			.socials {
-				(social("Screen source code", "https://github.com/obiwac/24h-lln-screen", include_static!("/icons/gh.svg")))
+				(social("Screen source code", "https://github.com/obiwac/24h-lln-screen", Icon::GitHub))
 			}
 		},
 		html! {
@@ -359,7 +391,6 @@ pub fn _24hvelo() -> Markup {
 	)
 }
 
-#[get("/batman")]
 pub fn batman() -> Markup {
 	explanation_page(
 		"B.A.T.M.A.N. 🦇",
@@ -379,14 +410,14 @@ pub fn batman() -> Markup {
 				" which is actually awesome 💙)."
 			}
 			.socials {
-				(social("Source code", "https://github.com/obiwac/freebsd-gsoc", include_static!("/icons/gh.svg")))
-				(social("FreeBSD wiki page", "https://wiki.freebsd.org/SummerOfCode2023Projects/CallingTheBatmanFreeNetworksOnFreeBSD", include_static!("/icons/fbsd.svg")))
-				(social("GSoC page", "https://summerofcode.withgoogle.com/archive/2023/projects/9YX3dONN", include_static!("/icons/link.svg")))
+				(social("Source code", "https://github.com/obiwac/freebsd-gsoc", Icon::GitHub))
+				(social("FreeBSD wiki page", "https://wiki.freebsd.org/SummerOfCode2023Projects/CallingTheBatmanFreeNetworksOnFreeBSD", Icon::FreeBSD))
+				(social("GSoC page", "https://summerofcode.withgoogle.com/archive/2023/projects/9YX3dONN", Icon::Link))
 			}
 		},
 		html! {
 			.presentation {
-				iframe src="https://www.youtube.com/embed/BAVogweBQ8M?list=PLeF8ZihVdpFfct_WnzwObWtj4y9qH3H7X" title="Calling the BATMAN: Free Networks on FreeBSD By: Aymeric Wibo" allow="accelerometer; autoplay; clipboard-write; encrypted-media; gyroscope; picture-in-picture; web-share" referrerpolicy="strict-origin-when-cross-origin" allowfullscreen frameborder="0" {}
+				(embed!(BATMAN_TALK))
 				/* TODO I need to buy these fonts to be able to use them here!
 				style {"
 					@font-face {
@@ -400,22 +431,37 @@ pub fn batman() -> Markup {
 					}
 				"}
 				*/
-				iframe src="/public/batman/presentation.html" allowfullscreen {}
+				iframe title="Presentation slides" src="/public/batman/presentation.html" allowfullscreen {}
 			}
 		},
 	)
 }
 
+// Every page by route, which the routes themselves are mounted from, and which is also used to render them outside of a request (e.g. for the search index).
+
+pub type Page = fn() -> Markup;
+
+pub const PAGES: &[(&str, Page)] = &[
+	("/mcpy", mcpy),
+	("/bfm", bfm),
+	("/moodle", moodle),
+	("/gdpr", gdpr),
+	("/karwa", karwa),
+	("/graphic-design", graphic_design),
+	("/x-compositing-wm", x_compositing_wm),
+	("/24hvelo", _24hvelo),
+	("/batman", batman),
+];
+
 pub fn project_page_routes() -> Vec<rocket::Route> {
-	routes![
-		mcpy,
-		bfm,
-		moodle,
-		gdpr,
-		karwa,
-		graphic_design,
-		x_compositing_wm,
-		_24hvelo,
-		batman
-	]
+	PAGES
+		.iter()
+		.map(|&(route, page)| {
+			let handler = for<'r, 'x> move |req: &'r rocket::Request<'x>,
+			                                _: rocket::Data<'r>|
+			                  -> rocket::route::BoxFuture<'r> { Outcome::from(req, page()).pin() };
+
+			rocket::route::Route::new(rocket::http::Method::Get, route, handler)
+		})
+		.collect()
 }
@@ -0,0 +1,70 @@
+use rocket::http::ContentType;
+use rocket::route::Outcome;
+
+use crate::site;
+
+// Generated by 'build.rs' from 'site::ICON'.
+
+include!(concat!(env!("OUT_DIR"), "/pwa.rs"));
+
+#[get("/manifest.webmanifest")]
+pub fn manifest() -> (ContentType, String) {
+	let icons: Vec<String> = site::ICON_SIZES
+		.iter()
+		.flat_map(|size| {
+			[("icon", "any"), ("maskable", "maskable")].map(|(name, purpose)| {
+				format!(
+					r#"{{
+				"src": "/pwa/{}-{}.png",
+				"sizes": "{}x{}",
+				"type": "image/png",
+				"purpose": "{}"
+			}}"#,
+					name, size, size, size, purpose
+				)
+			})
+		})
+		.collect();
+
+	let manifest = format!(
+		r#"{{
+		"name": "{}",
+		"short_name": "{}",
+		"description": "{}",
+		"start_url": "/",
+		"scope": "/",
+		"display": "standalone",
+		"theme_color": "{}",
+		"background_color": "{}",
+		"icons": [
+			{}
+		]
+	}}"#,
+		site::NAME,
+		site::SHORT_NAME,
+		site::DESCRIPTION,
+		site::THEME_COLOUR,
+		site::BACKGROUND_COLOUR,
+		icons.join(", ")
+	);
+
+	(ContentType::new("application", "manifest+json"), manifest)
+}
+
+pub fn pwa_routes() -> Vec<rocket::Route> {
+	let mut routes: Vec<rocket::Route> = FILES
+		.iter()
+		.map(|&(route, content_type, contents)| {
+			let handler =
+				for<'r, 'x> move |req: &'r rocket::Request<'x>, _: rocket::Data<'r>| -> rocket::route::BoxFuture<'r> {
+					let content_type = ContentType::parse_flexible(content_type).unwrap();
+					Outcome::from(req, (content_type, contents)).pin()
+				};
+
+			rocket::route::Route::new(rocket::http::Method::Get, route, handler)
+		})
+		.collect();
+
+	routes.extend(routes![manifest]);
+	routes
+}
@@ -0,0 +1,105 @@
+use maud::{html, Markup, DOCTYPE};
+use rocket::response;
+use rocket::route::Outcome;
+
+use crate::export::pages;
+use crate::site;
+
+// Old URLs we want to keep alive, and short links to give out (e.g. on slides).
+// Targets starting with a '/' are internal, and may point to an anchor on the page.
+
+pub struct Redirect {
+	pub from: &'static str,
+	pub to: &'static str,
+	pub permanent: bool, /* Short links should generally be temporary, in case we want to point them somewhere else later. */
+}
+
+pub const REDIRECTS: &[Redirect] = &[
+	Redirect {
+		from: "/fosdem25",
+		to: "/s0ix#background",
+		permanent: false,
+	},
+	Redirect {
+		from: "/fosdem25/video",
+		to: "https://youtu.be/mBxj_EkAzV0",
+		permanent: false,
+	},
+	Redirect {
+		from: "/s0i3",
+		to: "/s0ix",
+		permanent: true,
+	},
+];
+
+impl Redirect {
+	fn internal(&self) -> bool {
+		self.to.starts_with('/')
+	}
+
+	// GitHub Pages can't redirect, so the static export gets one of these stubs at each 'from' route instead.
+
+	pub fn stub(&self) -> Markup {
+		let canonical = if self.internal() {
+			format!("{}{}", site::URL, self.to)
+		} else {
+			self.to.to_string()
+		};
+
+		html! {
+			(DOCTYPE)
+
+			html lang="en" {
+				head {
+					meta charset="UTF-8";
+					meta name="robots" content="noindex";
+					meta http-equiv="refresh" content=(format!("0; url={}", self.to));
+					link rel="canonical" href=(canonical);
+					title { "Redirecting..." }
+				}
+
+				body {
+					a href=(self.to) { (self.to) }
+				}
+			}
+		}
+	}
+}
+
+pub fn redirect_routes() -> Vec<rocket::Route> {
+	let pages = pages();
+
+	REDIRECTS
+		.iter()
+		.map(|redirect| {
+			assert!(
+				!pages.iter().any(|page| page == redirect.from),
+				"redirect from \"{}\" would shadow an existing page",
+				redirect.from
+			);
+
+			assert!(
+				!redirect.internal() ||
+					pages
+						.iter()
+						.any(|page| redirect.to.split('#').next() == Some(page.as_str())),
+				"redirect from \"{}\" points to \"{}\", which doesn't exist",
+				redirect.from,
+				redirect.to
+			);
+
+			let handler =
+				for<'r, 'x> move |req: &'r rocket::Request<'x>, _: rocket::Data<'r>| -> rocket::route::BoxFuture<'r> {
+					let response = if redirect.permanent {
+						response::Redirect::permanent(redirect.to)
+					} else {
+						response::Redirect::temporary(redirect.to)
+					};
+
+					Outcome::from(req, response).pin()
+				};
+
+			rocket::route::Route::new(rocket::http::Method::Get, redirect.from, handler)
+		})
+		.collect()
+}
@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use std::ptr;
+
+use maud::Render;
+use rocket::fairing::AdHoc;
+use scraper::Html;
+
+use crate::blog::{Blog, PerPublished, BLOGS};
+use crate::index::{Project, PROJECTS, SIDE_PROJECTS};
+
+// Posts and projects related to each post, by how much their tags overlap and by the TF-IDF similarity of their text.
+// This is all worked out when the server starts (or the site is exported), rather than in the middle of whichever request first needs it, and again once a scheduled post goes live (see 'PerPublished').
+// Unpublished posts are left out entirely, so that they don't skew the IDF or show up anywhere before they're out.
+
+const MAX_RELATED: usize = 3;
+const MIN_SCORE: f64 = 0.1;
+const TAG_WEIGHT: f64 = 0.5;
+
+const STOP_WORDS: &[&str] = &[
+	"about", "also", "and", "are", "but", "can", "for", "from", "has", "have", "into", "its", "more", "not", "one",
+	"our", "that", "the", "their", "then", "there", "these", "this", "was", "which", "will", "with", "you", "your",
+];
+
+#[derive(Clone, Copy)]
+pub enum Item {
+	Blog(&'static Blog),
+	Project(&'static Project),
+}
+
+impl Item {
+	pub fn title(&self) -> &'static str {
+		match self {
+			Item::Blog(blog) => blog.title,
+			Item::Project(project) => project.title,
+		}
+	}
+
+	pub fn link(&self) -> &'static str {
+		match self {
+			Item::Blog(blog) => blog.route,
+			Item::Project(project) => project.link,
+		}
+	}
+
+	fn tags(&self) -> &'static [&'static str] {
+		match self {
+			Item::Blog(blog) => blog.tags,
+			Item::Project(project) => project.tags,
+		}
+	}
+
+	fn text(&self) -> String {
+		let html = match self {
+			Item::Blog(blog) => blog.content().render(),
+			Item::Project(project) => (project.descr)(),
+		};
+
+		let text: String = Html::parse_fragment(&html.into_string())
+			.root_element()
+			.text()
+			.collect();
+		format!("{} {}", self.title(), text)
+	}
+}
+
+struct Document {
+	item: Item,
+	weights: HashMap<String, f64>, // Normalised, so that the dot product of two documents is their cosine similarity.
+}
+
+fn words(text: &str) -> Vec<String> {
+	text.split(|c: char| !c.is_alphanumeric())
+		.map(str::to_lowercase)
+		.filter(|word| word.len() >= 3 && !STOP_WORDS.contains(&word.as_str()))
+		.collect()
+}
+
+fn documents() -> Vec<Document> {
+	let items: Vec<Item> = BLOGS
+		.iter()
+		.filter(|blog| blog.published())
+		.map(|blog| Item::Blog(blog))
+		.chain(
+			PROJECTS
+				.iter()
+				.chain(SIDE_PROJECTS.iter())
+				.map(|project| Item::Project(project)),
+		)
+		.collect();
+
+	let words: Vec<Vec<String>> = items.iter().map(|item| words(&item.text())).collect();
+	let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+
+	for document in &words {
+		for word in document.iter().collect::<HashSet<_>>() {
+			*document_frequency.entry(word).or_default() += 1;
+		}
+	}
+
+	items
+		.iter()
+		.zip(&words)
+		.map(|(&item, words)| {
+			let mut weights: HashMap<String, f64> = HashMap::new();
+
+			for word in words {
+				*weights.entry(word.clone()).or_default() += 1.0;
+			}
+
+			for (word, weight) in weights.iter_mut() {
+				let idf = (items.len() as f64 / document_frequency[word.as_str()] as f64).ln();
+				*weight = *weight / words.len() as f64 * idf;
+			}
+
+			let norm = weights.values().map(|weight| weight * weight).sum::<f64>().sqrt();
+
+			if norm > 0.0 {
+				weights.values_mut().for_each(|weight| *weight /= norm);
+			}
+
+			Document { item, weights }
+		})
+		.collect()
+}
+
+fn similarity(a: &Document, b: &Document) -> f64 {
+	let text: f64 = a
+		.weights
+		.iter()
+		.filter_map(|(word, weight)| b.weights.get(word).map(|other| weight * other))
+		.sum();
+
+	let (a_tags, b_tags) = (a.item.tags(), b.item.tags());
+	let shared = a_tags.iter().filter(|tag| b_tags.contains(tag)).count();
+	let total = a_tags.len() + b_tags.len() - shared;
+
+	let tags = if total > 0 { shared as f64 / total as f64 } else { 0.0 };
+
+	text + TAG_WEIGHT * tags
+}
+
+fn most_similar(this: &Document, documents: &[Document]) -> Vec<Item> {
+	let mut scored: Vec<(f64, Item)> = documents
+		.iter()
+		.filter(|document| !ptr::eq(*document, this))
+		.map(|document| (similarity(this, document), document.item))
+		.filter(|(score, _)| *score >= MIN_SCORE)
+		.collect();
+
+	scored.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+	scored.into_iter().take(MAX_RELATED).map(|(_, item)| item).collect()
+}
+
+pub struct Related(HashMap<&'static str, Vec<Item>>); // Route of each published post to what's related to it.
+
+impl Related {
+	pub fn build() -> Self {
+		let documents = documents();
+
+		let related = documents
+			.iter()
+			.filter_map(|document| match document.item {
+				Item::Blog(blog) => Some((blog.route, most_similar(document, &documents))),
+				Item::Project(_) => None,
+			})
+			.collect();
+
+		Related(related)
+	}
+
+	pub fn of(&self, blog: &Blog) -> &[Item] {
+		self.0.get(blog.route).map_or(&[], Vec::as_slice)
+	}
+}
+
+pub fn related() -> AdHoc {
+	AdHoc::on_ignite("Related posts", |rocket| async {
+		rocket.manage(PerPublished::new(Related::build))
+	})
+}
@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashMap};
+
+use maud::{html, Markup, PreEscaped, Render};
+use rocket::fairing::AdHoc;
+use rocket::http::ContentType;
+use rocket::State;
+use rust_stemmers::{Algorithm, Stemmer};
+use scraper::{Html, Selector};
+use serde_json::{json, Map, Value};
+
+use crate::asset::asset;
+use crate::base::base;
+use crate::blog::{PerPublished, BLOGS};
+use crate::common::levenshtein;
+use crate::icon::Icon;
+use crate::index::{PROJECTS, SIDE_PROJECTS};
+use crate::person::PEOPLE;
+use crate::project_pages::PAGES;
+
+// Full-text search over posts, projects and people.
+// The index is built when the server starts (or the site is exported), rather than in the middle of whichever request first needs it, and again once a scheduled post goes live (see 'PerPublished').
+// It's served as JSON too, for 'public/search.js' to search the static export with.
+
+const MAX_RESULTS: usize = 10;
+const TITLE_WEIGHT: f64 = 3.0; // Words in a title count as much as this many in the text.
+const TYPO_WEIGHT: f64 = 0.5;
+const SNIPPET_WORDS: usize = 12; // On either side of the first match.
+
+pub struct Document {
+	route: String,
+	title: &'static str,
+	kind: &'static str,
+	text: String,
+}
+
+pub struct Index {
+	documents: Vec<Document>,
+	terms: BTreeMap<String, Vec<(usize, f64)>>, // Stem to the documents it appears in, and how much.
+}
+
+// Text of the element matching 'selector', leaving out scripts (e.g. JSON-LD) and styles.
+
+fn text(html: &str, selector: &str) -> String {
+	let document = Html::parse_document(html);
+	let selector = Selector::parse(selector).unwrap();
+
+	let text: Vec<&str> = document
+		.select(&selector)
+		.flat_map(|element| element.descendants())
+		.filter(|node| {
+			!node
+				.parent()
+				.and_then(|parent| parent.value().as_element())
+				.is_some_and(|parent| ["script", "style"].contains(&parent.name()))
+		})
+		.filter_map(|node| node.value().as_text())
+		.flat_map(|text| text.split_whitespace())
+		.collect();
+
+	text.join(" ")
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+	text.split(|c: char| !c.is_alphanumeric())
+		.filter(|word| !word.is_empty())
+}
+
+fn stem(stemmer: &Stemmer, word: &str) -> String {
+	stemmer.stem(&word.to_lowercase()).into_owned()
+}
+
+// Project pages have more to say than their descriptions on the main page, so use those where there are any.
+
+fn documents() -> Vec<Document> {
+	let mut documents: Vec<Document> = BLOGS
+		.iter()
+		.filter(|blog| blog.published())
+		.map(|blog| Document {
+			route: blog.route.to_string(),
+			title: blog.title,
+			kind: "Article",
+			text: text(&blog.content().render().into_string(), "body"),
+		})
+		.collect();
+
+	documents.extend(PROJECTS.iter().chain(SIDE_PROJECTS.iter()).map(|project| {
+		let text = match PAGES.iter().find(|(route, _)| *route == project.link) {
+			Some((_, page)) => text(&page().into_string(), "main"),
+			None => text(&(project.descr)().into_string(), "body"),
+		};
+
+		Document {
+			route: project.link.to_string(),
+			title: project.title,
+			kind: "Project",
+			text,
+		}
+	}));
+
+	documents.extend(PEOPLE.iter().map(|person| Document {
+		route: person.route(),
+		title: person.name,
+		kind: "Person",
+		text: format!("{} ({})", person.name, person.handle),
+	}));
+
+	documents
+}
+
+pub fn build() -> Index {
+	let stemmer = Stemmer::create(Algorithm::English);
+	let documents = documents();
+	let mut counts: BTreeMap<String, HashMap<usize, f64>> = BTreeMap::new();
+
+	for (i, document) in documents.iter().enumerate() {
+		for (text, weight) in [(document.title, TITLE_WEIGHT), (&document.text, 1.0)] {
+			for word in words(text) {
+				*counts.entry(stem(&stemmer, word)).or_default().entry(i).or_default() += weight;
+			}
+		}
+	}
+
+	// TF-IDF, with the term frequency damped so that long posts don't drown everything else out.
+
+	let terms = counts
+		.into_iter()
+		.map(|(term, postings)| {
+			let idf = (documents.len() as f64 / postings.len() as f64).ln() + 1.0;
+
+			let mut postings: Vec<(usize, f64)> = postings
+				.into_iter()
+				.map(|(i, count)| (i, (1.0 + count.ln()) * idf))
+				.collect();
+
+			postings.sort_by_key(|(i, _)| *i);
+			(term, postings)
+		})
+		.collect();
+
+	Index { documents, terms }
+}
+
+pub fn index() -> AdHoc {
+	AdHoc::on_ignite("Search index", |rocket| async {
+		rocket.manage(PerPublished::new(build))
+	})
+}
+
+pub struct Hit<'a> {
+	pub document: &'a Document,
+	pub score: f64,
+	pub snippet: Markup,
+}
+
+impl Index {
+	// Terms from the index which a query word could be referring to, and how confident we are about that.
+	// Exact matches (after stemming) are taken as-is, and otherwise anything a typo or two away is.
+
+	fn matches(&self, stemmer: &Stemmer, word: &str) -> Vec<(&str, f64)> {
+		let term = stem(stemmer, word);
+
+		if let Some((term, _)) = self.terms.get_key_value(&term) {
+			return vec![(term.as_str(), 1.0)];
+		}
+
+		let tolerance = match term.chars().count() {
+			0..=3 => return Vec::new(),
+			4..=7 => 1,
+			_ => 2,
+		};
+
+		self.terms
+			.keys()
+			.filter(|candidate| levenshtein(&term, candidate) <= tolerance)
+			.map(|candidate| (candidate.as_str(), TYPO_WEIGHT))
+			.collect()
+	}
+
+	pub fn search(&self, query: &str) -> Vec<Hit<'_>> {
+		let stemmer = Stemmer::create(Algorithm::English);
+		let mut scores: HashMap<usize, f64> = HashMap::new();
+		let mut matched: Vec<&str> = Vec::new();
+
+		for word in words(query) {
+			for (term, confidence) in self.matches(&stemmer, word) {
+				matched.push(term);
+
+				for (i, weight) in &self.terms[term] {
+					*scores.entry(*i).or_default() += weight * confidence;
+				}
+			}
+		}
+
+		let mut results: Vec<Hit> = scores
+			.into_iter()
+			.map(|(i, score)| {
+				let document = &self.documents[i];
+
+				Hit {
+					document,
+					score,
+					snippet: snippet(&stemmer, &document.text, &matched),
+				}
+			})
+			.collect();
+
+		results.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.document.title.cmp(b.document.title)));
+		results.truncate(MAX_RESULTS);
+		results
+	}
+}
+
+// A few words around the first match in the text, with the matches highlighted.
+
+fn snippet(stemmer: &Stemmer, text: &str, matched: &[&str]) -> Markup {
+	let words: Vec<&str> = text.split_whitespace().collect();
+	let is_match = |word: &str| {
+		let word: String = word.chars().filter(|c| c.is_alphanumeric()).collect();
+		!word.is_empty() && matched.contains(&stem(stemmer, &word).as_str())
+	};
+
+	let first = words.iter().position(|word| is_match(word)).unwrap_or(0);
+	let start = first.saturating_sub(SNIPPET_WORDS);
+	let end = (first + SNIPPET_WORDS + 1).min(words.len());
+
+	html! {
+		@if start > 0 { "… " }
+		@for word in &words[start..end] {
+			@if is_match(word) {
+				mark { (word) }
+			} @else {
+				(word)
+			}
+			" "
+		}
+		@if end < words.len() { "…" }
+	}
+}
+
+// Compact form of the index, as '{"documents": [[route, title, kind], ...], "terms": {stem: [document, weight, ...], ...}}'.
+// Weights are rounded, as more precision than that would only make the file bigger.
+
+#[get("/search.json")]
+pub fn search_index(index: &State<PerPublished<Index>>) -> (ContentType, String) {
+	let index = index.current();
+	let documents: Vec<Value> = index
+		.documents
+		.iter()
+		.map(|document| json!([document.route, document.title, document.kind]))
+		.collect();
+
+	let terms: Map<String, Value> = index
+		.terms
+		.iter()
+		.map(|(term, postings)| {
+			let postings: Vec<Value> = postings
+				.iter()
+				.flat_map(|(i, weight)| [json!(i), json!((weight * 100.0).round() / 100.0)])
+				.collect();
+
+			(term.clone(), Value::Array(postings))
+		})
+		.collect();
+
+	let json = json!({
+		"documents": documents,
+		"terms": terms,
+	});
+
+	(ContentType::JSON, json.to_string())
+}
+
+pub fn form(query: &str) -> Markup {
+	html! {
+		form.search action="/search" method="get" role="search" {
+			label for="search-input" { "Search the site" }
+			input #search-input type="search" name="q" value=(query) required;
+			button type="submit" { "Search" }
+		}
+	}
+}
+
+// Without a server (i.e. on the static export), there won't be any results here, so 'search.js' fills them in from the JSON index instead.
+
+#[get("/search?<q>")]
+pub fn search(q: Option<&str>, index: &State<PerPublished<Index>>) -> Markup {
+	let query = q.unwrap_or_default().trim();
+	let index = index.current();
+	let results = index.search(query);
+
+	let schema = r#"{
+		"@context": "http://schema.org",
+		"@type": "SearchResultsPage",
+		"name": "Search"
+	}"#;
+
+	base(
+		"Search",
+		"Search through the articles, projects, and people on this site.",
+		PreEscaped(schema),
+		&[],
+		html! {
+			a.go-back href="/" {
+				(Icon::Back)
+				p { "Main page" }
+			}
+			main.blog-container {
+				h1.blog-title { "Search 🔎" }
+				(form(query))
+				@if !query.is_empty() {
+					section #search-results aria-live="polite" {
+						@if results.is_empty() {
+							p { "Nothing matches " q { (query) } "." }
+						} @else {
+							ol.search-results {
+								@for result in results {
+									li {
+										a.link href=(result.document.route) { (result.document.title) }
+										" "
+										small { (result.document.kind) }
+										p { (result.snippet) }
+									}
+								}
+							}
+						}
+					}
+				}
+				(asset!("/search.js"))
+			}
+		},
+	)
+}
@@ -0,0 +1,178 @@
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::LazyLock;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use maud::html;
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::{ContentType, Header};
+use rocket::shield::{Feature, Hsts, Permission, Referrer, Shield};
+use rocket::time::Duration;
+use rocket::{Request, Response};
+use sha2::{Digest, Sha256};
+
+use crate::base::main_css;
+use crate::project_pages::{EMBEDS, INLINE_SCRIPTS};
+
+// Static security headers.
+// Rocket's shield already sets 'X-Content-Type-Options: nosniff' and 'X-Frame-Options: SAMEORIGIN' by default.
+// Fullscreen, autoplay, &c are left alone, as the embedded videos need them.
+
+pub fn shield() -> Shield {
+	Shield::default()
+		.enable(Hsts::IncludeSubDomains(Duration::days(365)))
+		.enable(Referrer::StrictOriginWhenCrossOrigin)
+		.enable(
+			Permission::default()
+				.block(Feature::Camera)
+				.block(Feature::Microphone)
+				.block(Feature::Geolocation)
+				.block(Feature::Payment)
+				.block(Feature::Usb),
+		)
+}
+
+// Baseline policy, which is then extended with the inline blocks and embeds below.
+
+const POLICY: &[(&str, &[&str])] = &[
+	("default-src", &["'self'"]),
+	("script-src", &["'self'"]),
+	("style-src", &["'self'"]),
+	("style-src-attr", &["'unsafe-inline'"]), // For the odd 'style' attribute, which can't be hashed.
+	("img-src", &["'self'", "data:"]),
+	("media-src", &["'self'"]),
+	("frame-src", &["'self'"]),
+	("object-src", &["'none'"]),
+	("base-uri", &["'none'"]),
+	("form-action", &["'self'"]),
+	("frame-ancestors", &["'self'"]),
+];
+
+fn origin(url: &str) -> Option<String> {
+	let (scheme, rest) = url.split_once("://")?;
+
+	if scheme != "https" && scheme != "http" {
+		return None;
+	}
+
+	let host = rest.split(['/', '?', '#']).next()?;
+	Some(format!("{}://{}", scheme, host))
+}
+
+fn hash(content: &str) -> String {
+	format!("'sha256-{}'", BASE64_STANDARD.encode(Sha256::digest(content)))
+}
+
+// Everything pages may be allowed on top of the baseline policy, worked out once, as hashing 'main.css' means minifying it first.
+
+#[derive(Clone)]
+struct Allowances {
+	style: String,
+	scripts: Vec<(&'static str, String)>, // Each inline script, and its hash.
+	frames: Vec<(String, String)>,        // Each embed's URL (as it appears in pages), and its origin.
+}
+
+impl Allowances {
+	fn build() -> Self {
+		Allowances {
+			style: hash(&main_css().0),
+			scripts: INLINE_SCRIPTS.iter().map(|script| (*script, hash(script))).collect(),
+			frames: EMBEDS
+				.iter()
+				.map(|embed| {
+					let origin = origin(embed.src).expect("embeds must have an absolute URL");
+					(html! { (embed.src) }.into_string(), origin)
+				})
+				.collect(),
+		}
+	}
+}
+
+static ALLOWANCES: LazyLock<Allowances> = LazyLock::new(Allowances::build);
+
+// Dev builds read 'main.css' from disk on every request, so its hash is worked out again every time there.
+
+fn allowances() -> Cow<'static, Allowances> {
+	if cfg!(feature = "dev") {
+		Cow::Owned(Allowances::build())
+	} else {
+		Cow::Borrowed(&ALLOWANCES)
+	}
+}
+
+// Inline scripts and styles are allowed by hash rather than nonce, so that the exact same policy works in the static export.
+// Only the blocks the templates are known to emit are hashed, so anything which makes its way into a page some other way is still blocked.
+// Similarly, the only third-party frames allowed are those of our own embeds.
+// Each page ('html', which only needs to include its body) only gets what it actually contains, so e.g. MOOdle's inline script isn't allowed anywhere else.
+
+pub fn content_security_policy(html: &str) -> String {
+	let allowances = allowances();
+	let mut policy: Vec<(&str, Vec<&str>)> = POLICY
+		.iter()
+		.map(|(directive, sources)| (*directive, sources.to_vec()))
+		.collect();
+
+	let mut allow = |directive: &str, source| {
+		let (_, sources) = policy.iter_mut().find(|(name, _)| *name == directive).unwrap();
+
+		if !sources.contains(&source) {
+			sources.push(source);
+		}
+	};
+
+	allow("style-src", &allowances.style);
+
+	for (script, hash) in &allowances.scripts {
+		if html.contains(script) {
+			allow("script-src", hash);
+		}
+	}
+
+	for (src, origin) in &allowances.frames {
+		if html.contains(src.as_str()) {
+			allow("frame-src", origin);
+		}
+	}
+
+	policy
+		.iter()
+		.map(|(directive, sources)| format!("{} {}", directive, sources.join(" ")))
+		.collect::<Vec<String>>()
+		.join("; ")
+}
+
+// The same policy for 'base()' to put in a '<meta>' tag, which is all the static export gets, as GitHub Pages doesn't let us set headers.
+// 'frame-ancestors' is ignored in '<meta>' tags, so leave it out.
+
+pub fn content_security_policy_meta(html: &str) -> String {
+	content_security_policy(html)
+		.split("; ")
+		.filter(|directive| !directive.starts_with("frame-ancestors "))
+		.collect::<Vec<&str>>()
+		.join("; ")
+}
+
+pub struct ContentSecurityPolicy;
+
+#[rocket::async_trait]
+impl Fairing for ContentSecurityPolicy {
+	fn info(&self) -> Info {
+		Info {
+			name: "Content security policy",
+			kind: Kind::Response,
+		}
+	}
+
+	async fn on_response<'r>(&self, _: &'r Request<'_>, res: &mut Response<'r>) {
+		if res.content_type() != Some(ContentType::HTML) {
+			return;
+		}
+
+		let Ok(html) = res.body_mut().to_string().await else {
+			return;
+		};
+
+		res.set_header(Header::new("Content-Security-Policy", content_security_policy(&html)));
+		res.set_sized_body(html.len(), Cursor::new(html));
+	}
+}
@@ -0,0 +1,29 @@
+// Site-wide configuration.
+// This is also included by 'build.rs' (to generate the PWA icons), so it must only contain plain constants.
+
+pub const NAME: &str = "Aymeric Wibo";
+pub const SHORT_NAME: &str = "obiwac";
+pub const DESCRIPTION: &str = "Personal website for Aymeric Wibo";
+pub const URL: &str = "https://obiw.ac";
+
+pub const THEME_COLOUR: &str = "#000000";
+pub const BACKGROUND_COLOUR: &str = "#000000";
+
+// Source image for the favicon and all the PWA icons.
+
+pub const ICON: &str = "/icons/me.png";
+
+pub const ICON_SIZES: &[u32] = &[192, 512];
+pub const FAVICON_SIZES: &[u32] = &[16, 32, 48];
+pub const APPLE_TOUCH_ICON_SIZE: u32 = 180;
+
+// iOS doesn't generate a splash screen from the manifest, so we need to give it one for each (portrait) screen resolution we care about.
+// These are (width, height, device pixel ratio) in physical pixels.
+
+pub const STARTUP_IMAGES: &[(u32, u32, u32)] = &[
+	(1170, 2532, 3), // iPhone 12-14.
+	(1179, 2556, 3), // iPhone 14 Pro-15.
+	(1290, 2796, 3), // iPhone 14 Pro Max-15 Plus.
+	(1640, 2360, 2), // iPad Air.
+	(2048, 2732, 2), // iPad Pro 12.9".
+];
@@ -1,6 +1,8 @@
-use maud::{html, Markup, PreEscaped};
+use maud::{html, Markup, Render};
 
-pub fn social(handle: &'static str, link: &'static str, icon: PreEscaped<&str>) -> Markup {
+use crate::icon::Icon;
+
+pub fn social(handle: &'static str, link: &'static str, icon: Icon) -> Markup {
 	html! {
 		a.social href=(link) {
 			(icon)
@@ -8,3 +10,105 @@ pub fn social(handle: &'static str, link: &'static str, icon: PreEscaped<&str>)
 		}
 	}
 }
+
+// Accounts which are shared across pages.
+// Those which identify me get 'rel="me"', so that IndieAuth and Mastodon can verify them.
+
+pub struct SocialAccount {
+	pub handle: &'static str,
+	pub url: &'static str,
+	pub icon: Icon,
+	pub identity: bool,
+}
+
+impl SocialAccount {
+	// Within the h-card on the main page, identity accounts are also marked up as the card's URLs and emails.
+	// Those classes mean nothing outside of an h-card, so they're only added here.
+
+	pub fn in_card(&self) -> Markup {
+		let class = if !self.identity {
+			"social"
+		} else if self.url.starts_with("mailto:") {
+			"social u-email"
+		} else {
+			"social u-url"
+		};
+
+		self.link(class)
+	}
+
+	fn link(&self, class: &str) -> Markup {
+		html! {
+			a class=(class) rel=[self.identity.then_some("me")] href=(self.url) {
+				(self.icon)
+				p { (self.handle) }
+			}
+		}
+	}
+}
+
+impl Render for SocialAccount {
+	fn render(&self) -> Markup {
+		self.link("social")
+	}
+}
+
+pub const LINKEDIN: SocialAccount = SocialAccount {
+	handle: "awibo",
+	url: "https://www.linkedin.com/in/awibo",
+	icon: Icon::LinkedIn,
+	identity: true,
+};
+
+pub const GITHUB: SocialAccount = SocialAccount {
+	handle: "@obiwac",
+	url: "https://github.com/obiwac",
+	icon: Icon::GitHub,
+	identity: true,
+};
+
+pub const EMAIL: SocialAccount = SocialAccount {
+	handle: "me@obiw.ac",
+	url: "mailto:me@obiw.ac",
+	icon: Icon::Email,
+	identity: true,
+};
+
+pub const FREEBSD_EMAIL: SocialAccount = SocialAccount {
+	handle: "obiwac@freebsd.org",
+	url: "mailto:obiwac@freebsd.org",
+	icon: Icon::FreeBSD,
+	identity: true,
+};
+
+pub const YOUTUBE: SocialAccount = SocialAccount {
+	handle: "obiwac",
+	url: "https://youtube.com/obiwac",
+	icon: Icon::YouTube,
+	identity: true,
+};
+
+pub const DISCORD: SocialAccount = SocialAccount {
+	handle: "obiwac",
+	url: "https://discord.com/users/305047157197504522",
+	icon: Icon::Discord,
+	identity: true,
+};
+
+pub const WEBRING: SocialAccount = SocialAccount {
+	handle: "Webring",
+	url: "http://fuz.su",
+	icon: Icon::Bell,
+	identity: false,
+};
+
+pub const SOURCE_CODE: SocialAccount = SocialAccount {
+	handle: "Source code",
+	url: "https://github.com/obiwac/obiwac.github.io",
+	icon: Icon::GitHub,
+	identity: false,
+};
+
+// In the order they're shown on the main page.
+
+pub const ACCOUNTS: &[&SocialAccount] = &[&LINKEDIN, &GITHUB, &EMAIL, &FREEBSD_EMAIL, &YOUTUBE, &DISCORD, &WEBRING];
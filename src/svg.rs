@@ -0,0 +1,117 @@
+// Minifies an icon from 'public/icons' into its viewBox and the contents of the '<symbol>' it'll become.
+// This is shared with 'build.rs', which does it for every icon at compile time, and is only used at runtime by dev builds.
+
+fn attrs(tag: &str) -> Vec<(&str, &str)> {
+	let mut attrs = Vec::new();
+	let mut rest = tag;
+
+	while let Some(eq) = rest.find('=') {
+		let name = rest[..eq].trim();
+		let quote = rest[eq + 1..].chars().next().unwrap();
+		let value_start = eq + 2;
+		let value_len = rest[value_start..].find(quote).unwrap();
+
+		attrs.push((name, &rest[value_start..value_start + value_len]));
+		rest = &rest[value_start + value_len + 1..];
+	}
+
+	attrs
+}
+
+pub fn minify(svg: &str) -> (String, String) {
+	let mut view_box = None;
+	let mut inner = String::new();
+	let mut rest = svg;
+	let mut skip_depth = 0;
+
+	while let Some(start) = rest.find('<') {
+		let text = rest[..start].trim();
+
+		if skip_depth == 0 && !text.is_empty() {
+			inner.push_str(text);
+		}
+
+		rest = &rest[start..];
+
+		// Comments. Those starting with '<!--!' are license notices (looking at you Font Awesome), and must be kept.
+
+		if rest.starts_with("<!--") {
+			let end = rest.find("-->").unwrap() + 3;
+
+			if rest.starts_with("<!--!") && skip_depth == 0 {
+				inner.push_str(&rest[..end]);
+			}
+
+			rest = &rest[end..];
+			continue;
+		}
+
+		let end = rest.find('>').unwrap() + 1;
+		let tag = &rest[..end];
+		rest = &rest[end..];
+
+		// XML declaration.
+
+		if tag.starts_with("<?") {
+			continue;
+		}
+
+		// End tags.
+
+		if let Some(name) = tag.strip_prefix("</") {
+			let name = name.trim_end_matches('>').trim();
+
+			if skip_depth > 0 {
+				skip_depth -= 1;
+			} else if name != "svg" {
+				inner.push_str(&format!("</{}>", name));
+			}
+
+			continue;
+		}
+
+		// Start tags.
+		// We drop titles (we add our own), editor metadata, and IDs (which would otherwise clash when the same icon is used twice on a page).
+
+		let self_closing = tag.ends_with("/>");
+		let body = tag[1..tag.len() - if self_closing { 2 } else { 1 }].trim();
+		let (name, attr_str) = body.split_once(char::is_whitespace).unwrap_or((body, ""));
+
+		if skip_depth > 0 || matches!(name, "title" | "metadata" | "sodipodi:namedview") {
+			if !self_closing {
+				skip_depth += 1;
+			}
+
+			continue;
+		}
+
+		let attrs = attrs(attr_str);
+
+		if name == "svg" {
+			view_box = attrs
+				.iter()
+				.find(|(name, _)| *name == "viewBox")
+				.map(|(_, value)| value.to_string());
+			continue;
+		}
+
+		inner.push('<');
+		inner.push_str(name);
+
+		for (attr, value) in attrs {
+			if attr == "id" ||
+				attr.starts_with("xmlns") ||
+				attr.starts_with("inkscape:") ||
+				attr.starts_with("sodipodi:")
+			{
+				continue;
+			}
+
+			inner.push_str(&format!(" {}=\"{}\"", attr, value));
+		}
+
+		inner.push_str(if self_closing { "/>" } else { ">" });
+	}
+
+	(view_box.expect("icon has no viewBox"), inner)
+}
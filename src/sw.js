@@ -0,0 +1,92 @@
+// service worker, which makes the site work offline
+// 'VERSION', 'PRECACHE', and 'STALE_WHILE_REVALIDATE' are prepended by the server (see 'src/sw.rs')
+
+// GitHub Pages redirects e.g. '/s0ix' to '/s0ix/', and browsers refuse to use redirected responses for navigations
+// so strip the trailing slash when looking things up, and store a clean copy of the response when it was redirected
+
+function normalize(url) {
+	const path = url.pathname.length > 1 ? url.pathname.replace(/\/$/, "") : url.pathname
+	return path + url.search
+}
+
+async function clean(response) {
+	if (!response.redirected) {
+		return response
+	}
+
+	return new Response(await response.blob(), {
+		status: response.status,
+		statusText: response.statusText,
+		headers: response.headers,
+	})
+}
+
+async function precache() {
+	const cache = await caches.open(VERSION)
+
+	await Promise.all(PRECACHE.map(async path => {
+		const response = await fetch(path)
+
+		if (!response.ok) {
+			throw new Error(`failed to precache ${path} (${response.status})`)
+		}
+
+		await cache.put(path, await clean(response))
+	}))
+}
+
+self.addEventListener("install", event => {
+	event.waitUntil(precache().then(() => self.skipWaiting()))
+})
+
+// get rid of the caches of previous versions
+
+self.addEventListener("activate", event => {
+	event.waitUntil(
+		caches.keys()
+			.then(keys => Promise.all(keys.filter(key => key !== VERSION).map(key => caches.delete(key))))
+			.then(() => self.clients.claim())
+	)
+})
+
+async function stale_while_revalidate(request, path) {
+	const cache = await caches.open(VERSION)
+	const cached = await cache.match(path)
+
+	const fetched = fetch(request).then(async response => {
+		if (response.ok) {
+			await cache.put(path, await clean(response.clone()))
+		}
+
+		return response
+	})
+
+	if (cached) {
+		fetched.catch(() => {}) // we don't care if revalidation fails (e.g. because we're offline)
+		return cached
+	}
+
+	return fetched
+}
+
+async function cache_first(request, path) {
+	const cached = await caches.match(path, { cacheName: VERSION })
+	return cached || fetch(request)
+}
+
+self.addEventListener("fetch", event => {
+	const url = new URL(event.request.url)
+
+	if (event.request.method !== "GET" || url.origin !== self.location.origin) {
+		return
+	}
+
+	const path = normalize(url)
+
+	if (STALE_WHILE_REVALIDATE.includes(path)) {
+		event.respondWith(stale_while_revalidate(event.request, path))
+		return
+	}
+
+	event.respondWith(cache_first(event.request, path))
+})
@@ -0,0 +1,134 @@
+use std::path::Path;
+use std::sync::LazyLock;
+use std::{env, fs};
+
+use maud::Render;
+use rocket::http::ContentType;
+use scraper::{Html, Selector};
+use sha2::{Digest, Sha256};
+
+use crate::asset::INTEGRITY;
+use crate::common::{include_static_unsafe, relative};
+use crate::export::{files, pages};
+use crate::index::{thing, PROJECTS, SIDE_PROJECTS};
+use crate::project_pages::PAGES;
+use crate::{blog, site};
+
+// Hash of the binary (which everything outside of 'public/' is baked into) and of what's precached from 'public/', so that caches get invalidated as soon as anything changes.
+// Worked out at runtime rather than in 'build.rs', which would otherwise have to rerun on every change to the source.
+
+static VERSION: LazyLock<String> = LazyLock::new(|| {
+	let mut hasher = Sha256::new();
+	hasher.update(fs::read(env::current_exe().unwrap()).unwrap());
+
+	for route in precache() {
+		if let Some(path) = route.strip_prefix("/public/") {
+			hasher.update(fs::read(Path::new(relative!("/public")).join(path)).unwrap_or_default());
+		}
+	}
+
+	hasher.finalize()[..8]
+		.iter()
+		.map(|byte| format!("{:02x}", byte))
+		.collect()
+});
+
+// Assets 'main.css' depends on (i.e. the fonts).
+
+fn css_assets() -> Vec<String> {
+	let mut assets: Vec<String> = include_static_unsafe!("/main.css")
+		.split("url(")
+		.skip(1)
+		.filter_map(|rest| rest.split_once(')'))
+		.map(|(url, _)| url.trim_matches(['"', '\'']).to_string())
+		.collect();
+
+	assets.sort();
+	assets.dedup();
+	assets
+}
+
+// Scripts (and stylesheets) pages pull in through 'asset!', which are all the ones with an integrity hash.
+// 'main.css' is inlined rather than linked, and 'livereload.js' is only ever used by the dev server.
+
+fn assets() -> Vec<String> {
+	INTEGRITY
+		.iter()
+		.map(|(path, _)| *path)
+		.filter(|path| !["/main.css", "/livereload.js"].contains(path))
+		.map(|path| format!("/public{}", path))
+		.collect()
+}
+
+// Images on the pages we export (thumbnails, posters, and what's in projects' pages and posts), and whatever in 'public/' scripts load themselves (e.g. MOOdle's textures).
+
+fn images(scripts: &[String]) -> Vec<String> {
+	let mut html: String = PROJECTS
+		.iter()
+		.chain(SIDE_PROJECTS.iter())
+		.map(|project| thing(project).into_string())
+		.collect();
+
+	html.extend(PAGES.iter().map(|(_, page)| page().into_string()));
+	html.extend(blog::published().map(|blog| blog.content().render().into_string()));
+
+	let document = Html::parse_document(&html);
+	let mut images: Vec<String> = document
+		.select(&Selector::parse("img[src^='/public/']").unwrap())
+		.filter_map(|img| img.value().attr("src"))
+		.map(String::from)
+		.collect();
+
+	let path = |route: &str| Path::new(relative!("/public")).join(route.strip_prefix("/public/").unwrap_or_default());
+
+	for script in scripts {
+		let source = fs::read_to_string(path(script)).unwrap_or_default();
+
+		images.extend(
+			source
+				.split('"')
+				.filter(|string| string.starts_with("/public/") && path(string).is_file())
+				.map(String::from),
+		);
+	}
+
+	images.sort();
+	images.dedup();
+	images
+}
+
+fn precache() -> Vec<String> {
+	let mut precache = pages();
+
+	// Startup images are only ever needed when adding the site to the home screen on iOS, so they're not worth precaching.
+
+	precache.extend(
+		files()
+			.into_iter()
+			.filter(|file| file != "/sw.js" && !file.starts_with("/pwa/startup-")),
+	);
+
+	let assets = assets();
+
+	precache.extend(css_assets());
+	precache.extend(images(&assets));
+	precache.extend(assets);
+	precache.push(format!("/public{}", site::ICON));
+
+	precache
+}
+
+#[get("/sw.js")]
+pub fn sw() -> (ContentType, String) {
+	let stale_while_revalidate: Vec<&str> = blog::published().map(|blog| blog.route).collect();
+
+	let sw = format!(
+		"const VERSION = \"{}\"\nconst PRECACHE = {:?}\nconst STALE_WHILE_REVALIDATE = {:?}\n\n{}",
+		*VERSION,
+		precache(),
+		stale_while_revalidate,
+		include_str!("sw.js")
+	);
+
+	(ContentType::JavaScript, sw)
+}
@@ -0,0 +1,735 @@
+use std::panic;
+
+use base64::prelude::{Engine, BASE64_STANDARD};
+use maud::Render;
+use rocket::http::{ContentType, Status};
+use rocket::local::blocking::Client;
+use scraper::{ElementRef, Html, Node, Selector};
+use sha2::{Digest, Sha256};
+
+use crate::a11y::lint;
+use crate::blog::{BLOGS, TODAY};
+use crate::common::Markdown;
+use crate::export::{files, pages};
+use crate::icon::CSS_ONLY;
+use crate::index::{PROJECTS, SIDE_PROJECTS};
+use crate::links::{check, KNOWN_MISSING};
+use crate::person::{Person, ALEXIS, NOA, PEOPLE};
+use crate::postprocess::{minify, validate};
+use crate::project_pages::{INLINE_SCRIPTS, PAGES};
+use crate::redirect::REDIRECTS;
+use crate::{rocket, site};
+
+fn client() -> Client {
+	Client::tracked(rocket()).expect("valid rocket instance")
+}
+
+fn get(client: &Client, route: &str) -> String {
+	let response = client.get(route).dispatch();
+
+	assert_eq!(response.status(), Status::Ok, "{} didn't return 200", route);
+	response.into_string().unwrap_or_default()
+}
+
+fn page(client: &Client, route: &str) -> Html {
+	let response = client.get(route).dispatch();
+
+	assert_eq!(response.status(), Status::Ok, "{} didn't return 200", route);
+	assert_eq!(response.content_type(), Some(ContentType::HTML), "{} isn't HTML", route);
+
+	let html = response.into_string().unwrap();
+	let errors = validate(&html);
+	assert!(errors.is_empty(), "{} rendered invalid HTML: {:?}", route, errors);
+
+	Html::parse_document(&html)
+}
+
+fn select<'a>(document: &'a Html, selector: &str) -> Vec<ElementRef<'a>> {
+	document.select(&Selector::parse(selector).unwrap()).collect()
+}
+
+#[test]
+fn pages_render() {
+	let client = client();
+
+	for route in pages() {
+		let document = page(&client, &route);
+
+		let title: String = select(&document, "head > title")
+			.iter()
+			.flat_map(|title| title.text())
+			.collect();
+		assert!(!title.trim().is_empty(), "{} has no title", route);
+
+		let description = select(&document, "meta[name=description]")
+			.first()
+			.and_then(|meta| meta.value().attr("content"))
+			.unwrap_or_else(|| panic!("{} has no description", route))
+			.to_string();
+
+		assert!(!description.is_empty(), "{} has an empty description", route);
+		assert!(description.len() <= 275, "{}'s description is too long", route);
+
+		let schemas = select(&document, "script[type=\"application/ld+json\"]");
+		assert!(!schemas.is_empty(), "{} has no JSON-LD", route);
+
+		for schema in schemas {
+			let schema: String = schema.text().collect();
+
+			if let Err(err) = serde_json::from_str::<serde_json::Value>(&schema) {
+				panic!("{} has invalid JSON-LD ({}):\n{}", route, err, schema);
+			}
+		}
+	}
+}
+
+#[test]
+fn accessibility() {
+	let client = client();
+	let mut errors = Vec::new();
+
+	for route in pages() {
+		let document = page(&client, &route);
+		errors.extend(lint(&document).into_iter().map(|error| format!("{}: {}", route, error)));
+	}
+
+	assert!(errors.is_empty(), "accessibility issues:\n{}", errors.join("\n"));
+}
+
+#[test]
+fn files_serve() {
+	let client = client();
+
+	for route in files() {
+		get(&client, &route);
+	}
+}
+
+// Everything the service worker precaches has to exist, or installing it fails.
+
+#[test]
+fn precache() {
+	let client = client();
+	let sw = get(&client, "/sw.js");
+
+	let precache: Vec<String> = sw
+		.lines()
+		.find_map(|line| line.strip_prefix("const PRECACHE = "))
+		.map(|precache| serde_json::from_str(precache).unwrap())
+		.unwrap();
+
+	for route in &precache {
+		assert_eq!(
+			client.get(route.as_str()).dispatch().status(),
+			Status::Ok,
+			"{} isn't there",
+			route
+		);
+	}
+
+	let expected = PROJECTS
+		.iter()
+		.chain(SIDE_PROJECTS.iter())
+		.map(|project| project.img_src)
+		.chain([
+			"/public/search.js",
+			"/public/moodle/models/holstein.js",
+			"/public/moodle/textures/holstein.png",
+		])
+		.chain(["/public/blog/zzz.png", "/public/graphic-design/gimp.webp"]);
+
+	for route in expected {
+		assert!(precache.iter().any(|other| other == route), "{} isn't precached", route);
+	}
+}
+
+#[test]
+fn css_only_icons() {
+	let css = get(&client(), "/public/main.css");
+
+	for name in CSS_ONLY {
+		assert!(
+			css.contains(&format!("url(/public/icons/{}.svg)", name)),
+			"'main.css' doesn't use the {} icon, so take it off 'CSS_ONLY'",
+			name
+		);
+	}
+}
+
+#[rocket::async_test]
+async fn links_resolve() {
+	let client = rocket::local::asynchronous::Client::tracked(rocket()).await.unwrap();
+	let report = check(&client).await;
+
+	assert!(report.broken.is_empty(), "{}", report);
+
+	for asset in KNOWN_MISSING {
+		assert!(
+			report.missing.iter().any(|(_, missing)| missing.starts_with(asset)),
+			"{} isn't linked to anymore, so take it off 'KNOWN_MISSING'",
+			asset
+		);
+	}
+}
+
+#[test]
+fn not_found() {
+	let client = client();
+	let response = client.get("/this/does/not/exist").dispatch();
+
+	assert_eq!(response.status(), Status::NotFound);
+	assert_eq!(response.content_type(), Some(ContentType::HTML));
+	assert!(response.into_string().unwrap().contains("/this/does/not/exist"));
+
+	// The static export's '404.html' is served for every missing path, so it mustn't name one.
+
+	let response = client.get("/404.html").dispatch();
+	let body = response.into_string().unwrap();
+
+	assert!(body.contains("There's nothing here."));
+	assert!(!body.contains("There's nothing at"));
+}
+
+// Only the inline blocks the templates are known to emit are allowed, so make sure they're all accounted for (minified or not).
+
+#[test]
+fn inline_blocks_allowed() {
+	let client = client();
+
+	for route in pages() {
+		let response = client.get(&route).dispatch();
+		let csp = response
+			.headers()
+			.get_one("Content-Security-Policy")
+			.unwrap()
+			.to_string();
+		let html = response.into_string().unwrap();
+
+		for html in [minify(&html), html] {
+			let document = Html::parse_document(&html);
+
+			for block in select(&document, "script:not([src]):not([type]), style") {
+				let hash = BASE64_STANDARD.encode(Sha256::digest(block.text().collect::<String>()));
+				assert!(
+					csp.contains(&hash),
+					"{} has an inline block which the CSP doesn't allow",
+					route
+				);
+			}
+
+			let meta = select(&document, "meta[http-equiv=Content-Security-Policy]");
+			assert_eq!(meta.len(), 1, "{} should have exactly one CSP '<meta>' tag", route);
+			assert!(csp.starts_with(meta[0].value().attr("content").unwrap()));
+		}
+	}
+}
+
+// Pages only allow the inline scripts and embeds which they actually have.
+
+#[test]
+fn csp_scoped() {
+	let client = client();
+	let moodle = BASE64_STANDARD.encode(Sha256::digest(INLINE_SCRIPTS[0]));
+
+	for (route, script, frame) in [("/", false, false), ("/moodle", true, false), ("/gdpr", false, true)] {
+		let response = client.get(route).dispatch();
+		let csp = response.headers().get_one("Content-Security-Policy").unwrap();
+
+		assert_eq!(
+			csp.contains(&moodle),
+			script,
+			"{} has the wrong inline scripts allowed",
+			route
+		);
+		assert_eq!(
+			csp.contains("https://novation.dev"),
+			frame,
+			"{} has the wrong frames allowed",
+			route
+		);
+	}
+}
+
+#[test]
+fn redirects() {
+	let client = client();
+
+	for redirect in REDIRECTS {
+		let response = client.get(redirect.from).dispatch();
+		let expected = if redirect.permanent {
+			Status::PermanentRedirect
+		} else {
+			Status::TemporaryRedirect
+		};
+
+		assert_eq!(response.status(), expected, "{} has the wrong status", redirect.from);
+		assert_eq!(response.headers().get_one("Location"), Some(redirect.to));
+	}
+}
+
+#[test]
+fn tabs() {
+	let client = client();
+
+	for (route, tab) in [
+		("/", None),
+		("/projects", Some("projects-tab-input")),
+		("/articles", Some("articles-tab-input")),
+	] {
+		let document = page(&client, route);
+		let checked: Vec<&str> = select(&document, "input[name=tab][checked]")
+			.iter()
+			.filter_map(|input| input.value().id())
+			.collect();
+
+		assert_eq!(
+			checked,
+			tab.into_iter().collect::<Vec<_>>(),
+			"{} has the wrong tab checked",
+			route
+		);
+
+		let canonical = select(&document, "link[rel=canonical]");
+		assert_eq!(
+			canonical
+				.iter()
+				.map(|link| link.value().attr("href"))
+				.collect::<Vec<_>>(),
+			[Some(format!("{}/", site::URL).as_str())],
+			"{} should be canonicalised to '/'",
+			route
+		);
+	}
+}
+
+#[test]
+fn search() {
+	let client = client();
+
+	// Typos should still find what they're meant to.
+
+	for query in ["fingerprint", "fingerprnt", "suspending"] {
+		let document = page(&client, &format!("/search?q={}", query));
+		let results = select(&document, "#search-results a");
+
+		assert!(!results.is_empty(), "no results for \"{}\"", query);
+	}
+
+	let index: serde_json::Value = serde_json::from_str(&get(&client, "/search.json")).unwrap();
+	assert!(index["documents"]
+		.as_array()
+		.is_some_and(|documents| !documents.is_empty()));
+}
+
+// Everyone linked to (through their page or their own site) in some HTML, in order, to check projects' and posts' 'people' against.
+
+fn linked_people(html: &str) -> Vec<&'static str> {
+	let document = Html::parse_fragment(html);
+	let mut people = Vec::new();
+
+	for link in select(&document, "a[href]") {
+		let href = link.value().attr("href").unwrap();
+		let linked = PEOPLE
+			.iter()
+			.find(|person| href == person.route() || href == person.url);
+
+		if let Some(person) = linked.filter(|person| !people.contains(&person.handle)) {
+			people.push(person.handle);
+		}
+	}
+
+	people
+}
+
+#[test]
+fn mentions() {
+	let handles = |people: &[&Person]| -> Vec<&str> { people.iter().map(|person| person.handle).collect() };
+
+	for project in PROJECTS.iter().chain(SIDE_PROJECTS.iter()) {
+		let mut html = (project.descr)().into_string();
+
+		if let Some((_, page)) = PAGES.iter().find(|(route, _)| *route == project.link) {
+			html.push_str(&page().into_string());
+		}
+
+		assert_eq!(
+			handles(project.people),
+			linked_people(&html),
+			"{}'s people",
+			project.title
+		);
+	}
+
+	for blog in BLOGS {
+		let html = blog.content().render().into_string();
+		assert_eq!(handles(blog.people), linked_people(&html), "{}'s people", blog.route);
+	}
+
+	// A person's page mentions who they worked with, but not themselves.
+
+	let client = client();
+	let document = page(&client, &NOA.route());
+
+	let mentioned: Vec<String> = select(&document, "script[type='application/ld+json']")
+		.iter()
+		.filter_map(|script| serde_json::from_str::<serde_json::Value>(&script.text().collect::<String>()).ok())
+		.filter_map(|json| json["@graph"].as_array().cloned())
+		.flatten()
+		.map(|person| person["name"].as_str().unwrap().to_string())
+		.collect();
+
+	assert!(mentioned.contains(&ALEXIS.name.to_string()), "{:?}", mentioned);
+	assert!(!mentioned.contains(&NOA.name.to_string()), "{:?}", mentioned);
+}
+
+#[test]
+fn math() {
+	let markdown = Markdown(
+		"See $\\eqref{energy}$, where $c$ is the speed of light.\n\n$$\n\\label{energy} E = mc^2\n$$\n\n$$a^2 + b^2 = \
+		 c^2$$",
+	);
+	let document = Html::parse_fragment(&markdown.render().into_string());
+
+	assert_eq!(
+		select(&document, "math").len(),
+		4,
+		"math wasn't rendered to MathML (or was sanitised away)"
+	);
+	assert_eq!(select(&document, "math[display=block]").len(), 2);
+
+	let numbers: Vec<String> = select(&document, ".equation-number")
+		.iter()
+		.map(|number| number.text().collect())
+		.collect();
+	assert_eq!(numbers, ["(1)"], "only labelled equations should be numbered");
+
+	let reference = select(&document, "a[href='#eq-energy']");
+	assert_eq!(
+		reference.first().map(|a| a.text().collect::<String>()),
+		Some("(1)".to_string())
+	);
+	assert_eq!(select(&document, "#eq-energy").len(), 1);
+
+	// References can be anywhere in a formula (inline or not), and are only numbers inside links, as links can't go in links.
+
+	for (reference, links) in [
+		("$\\text{by } \\eqref{energy}$", 1),
+		("$\\left(\\eqref{energy}\\right)$", 1),
+		("$\\frac{1}{\\eqref{energy}}$", 1),
+		("$$F = \\eqref{energy}$$", 1),
+		("[see $\\eqref{energy}$](/)", 0),
+	] {
+		let markdown = format!("{}\n\n$$\n\\label{{energy}} E = mc^2\n$$", reference);
+		let html = Markdown(&markdown).render().into_string();
+		let document = Html::parse_fragment(&html);
+
+		assert_eq!(
+			select(&document, "a[href='#eq-energy']").len(),
+			links,
+			"{:?}",
+			reference
+		);
+		assert!(
+			select(&document, "a a").is_empty(),
+			"{:?} has a link in a link",
+			reference
+		);
+		assert!(
+			select(&document, ".math-error").is_empty(),
+			"{:?} is an error",
+			reference
+		);
+		assert_eq!(
+			validate(&format!("<!DOCTYPE html><title>Math</title>{}", html)),
+			Vec::<String>::new(),
+			"{:?} is invalid HTML",
+			reference
+		);
+		assert_eq!(
+			select(&document, "math mtext")
+				.iter()
+				.filter(|mtext| mtext.text().any(|text| text == "(1)"))
+				.count(),
+			1,
+			"{:?} doesn't refer to equation 1",
+			reference
+		);
+	}
+
+	// Bad LaTeX (whether latex2mathml fails or renders its error), references to equations which don't exist, labels used twice, and display math in links are all shown as is, rather than panicking.
+
+	for markdown in [
+		"$\\left( x$",
+		"$\\frac{1}$",
+		"$\\notacommand x$",
+		"$\\eqref{nothing}$",
+		"$$\\label{twice} a$$\n\n$$\\label{twice} b$$",
+		"[$$x$$](/)",
+	] {
+		let html = Markdown(markdown).render().into_string();
+		let document = Html::parse_fragment(&html);
+
+		assert!(
+			!select(&document, ".math-error").is_empty(),
+			"{:?} should be an error",
+			markdown
+		);
+		assert!(
+			!html.contains("PARSE ERROR"),
+			"{:?} shows latex2mathml's error",
+			markdown
+		);
+	}
+}
+
+// Every post (drafts included) should have all its math render, since errors only get logged.
+
+#[test]
+fn math_renders() {
+	for blog in BLOGS {
+		let html = blog.content().render().into_string();
+		assert!(
+			!html.contains("math-error"),
+			"{} has math which doesn't render",
+			blog.route
+		);
+	}
+}
+
+#[test]
+fn preview_needs_token() {
+	let client = client();
+	let response = client.get("/preview/not-a-token").dispatch();
+
+	assert_eq!(response.status(), Status::NotFound);
+}
+
+// The draft fixture (see 'BLOGS') mustn't be reachable, linked, or indexed anywhere.
+
+const DRAFT: &str = "/draft-fixture";
+
+#[test]
+fn drafts_hidden() {
+	let client = client();
+
+	for route in [DRAFT.to_string(), format!("{}/history", DRAFT)] {
+		assert_eq!(
+			client.get(&route).dispatch().status(),
+			Status::NotFound,
+			"{} should 404",
+			route
+		);
+	}
+
+	// Nor should its source, or any other post's for that matter, as they're only ever served rendered.
+
+	for blog in BLOGS {
+		let route = format!("/public{}", blog.source);
+		assert_eq!(
+			client.get(&route).dispatch().status(),
+			Status::NotFound,
+			"{} should 404",
+			route
+		);
+	}
+
+	// That's every page and file we export, including the index, archive, search index, and the other posts (with their related posts).
+
+	for route in pages().iter().chain(files().iter()) {
+		assert!(!route.starts_with(DRAFT), "{} shouldn't be exported", route);
+		assert!(!get(&client, route).contains(DRAFT), "{} refers to the draft", route);
+	}
+
+	let document = page(&client, "/search?q=unpublishable");
+	assert!(select(&document, "#search-results a").is_empty());
+}
+
+// Scheduled posts go live by themselves, search index and related posts included, without restarting anything.
+
+const SCHEDULED: &str = "/scheduled-fixture";
+
+#[test]
+fn scheduled() {
+	let client = client();
+	let searched = |client: &Client| {
+		let document = page(client, "/search?q=scheduled+fixture");
+		select(&document, "#search-results a")
+			.iter()
+			.any(|result| result.value().attr("href") == Some(SCHEDULED))
+	};
+	let related = |client: &Client, route: &str| {
+		let document = page(client, route);
+		select(&document, ".related a")
+			.iter()
+			.filter_map(|link| link.value().attr("href").map(String::from))
+			.collect::<Vec<_>>()
+	};
+
+	assert_eq!(client.get(SCHEDULED).dispatch().status(), Status::NotFound);
+	assert!(!searched(&client));
+	assert!(!get(&client, "/search.json").contains(SCHEDULED));
+
+	TODAY.set(Some("2100-01-01"));
+
+	assert!(searched(&client), "{} isn't in the search results", SCHEDULED);
+	assert!(get(&client, "/search.json").contains(SCHEDULED));
+	assert!(
+		!related(&client, SCHEDULED).is_empty(),
+		"{} has no related posts",
+		SCHEDULED
+	);
+	assert!(
+		BLOGS
+			.iter()
+			.filter(|blog| blog.published() && blog.route != SCHEDULED)
+			.any(|blog| related(&client, blog.route).iter().any(|route| route == SCHEDULED)),
+		"{} isn't related to anything",
+		SCHEDULED
+	);
+
+	TODAY.set(None);
+
+	assert_eq!(client.get(SCHEDULED).dispatch().status(), Status::NotFound);
+	assert!(!searched(&client));
+}
+
+#[test]
+fn preview() {
+	let secret = "hunter2";
+	let rocket = rocket().configure(rocket::Config::figment().merge(("preview_secret", secret)));
+	let client = Client::tracked(rocket).expect("valid rocket instance");
+
+	let draft = BLOGS.iter().find(|blog| blog.route == DRAFT).unwrap();
+	let response = client
+		.get(format!("/preview/{}", draft.preview_token(secret)))
+		.dispatch();
+
+	assert_eq!(response.status(), Status::Ok);
+	assert_eq!(response.headers().get_one("X-Robots-Tag"), Some("noindex"));
+
+	let document = Html::parse_document(&response.into_string().unwrap());
+	assert_eq!(select(&document, ".draft-banner").len(), 1);
+	assert!(document
+		.root_element()
+		.text()
+		.any(|text| text.contains("unpublishable")));
+
+	// Published posts are already out, so they don't get previews.
+
+	let published = BLOGS.iter().find(|blog| blog.published()).unwrap();
+	let response = client
+		.get(format!("/preview/{}", published.preview_token(secret)))
+		.dispatch();
+
+	assert_eq!(response.status(), Status::NotFound);
+}
+
+// Normalised and pretty-printed HTML for snapshots.
+// That's one tag or line of text per line with sorted attributes, and with the noisy bits (inline CSS, integrity and CSP hashes, anything from the git history) redacted so that snapshots only change when the markup does.
+
+const VOID_ELEMENTS: &[&str] = &[
+	"area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+const GIT_HISTORY_CLASSES: &[&str] = &["last-updated", "history"];
+
+// Hashes in the CSP change whenever the inline blocks they allow do.
+
+fn redact_hash(source: &str) -> &str {
+	if source.starts_with("'sha256-") {
+		"'sha256-[hash]'"
+	} else {
+		source
+	}
+}
+
+fn pretty(element: ElementRef, depth: usize, out: &mut String) {
+	let indent = "\t".repeat(depth);
+	let name = element.value().name();
+
+	let mut attrs: Vec<(&str, &str)> = element.value().attrs().collect();
+	attrs.sort();
+
+	let attrs: String = attrs
+		.iter()
+		.map(|&(attr, value)| match attr {
+			"integrity" => format!(" {}=\"[integrity]\"", attr),
+			"content" if element.value().attr("http-equiv") == Some("Content-Security-Policy") => {
+				let policy: Vec<String> = value
+					.split("; ")
+					.map(|directive| directive.split(' ').map(redact_hash).collect::<Vec<_>>().join(" "))
+					.collect();
+
+				format!(" {}=\"{}\"", attr, policy.join("; ").render().into_string())
+			}
+			_ => format!(" {}=\"{}\"", attr, value.render().into_string()),
+		})
+		.collect();
+
+	out.push_str(&format!("{}<{}{}>\n", indent, name, attrs));
+
+	if VOID_ELEMENTS.contains(&name) {
+		return;
+	}
+
+	let from_git_history = element
+		.value()
+		.classes()
+		.any(|class| GIT_HISTORY_CLASSES.contains(&class));
+
+	match name {
+		_ if from_git_history => out.push_str(&format!("{}\t[git history]\n", indent)),
+		"style" => out.push_str(&format!("{}\t[inline CSS]\n", indent)),
+		"pre" => out.push_str(&format!("{}\n", element.inner_html())), // Whitespace is significant here.
+		_ => {
+			for child in element.children() {
+				match child.value() {
+					Node::Element(_) => pretty(ElementRef::wrap(child).unwrap(), depth + 1, out),
+					Node::Text(text) => {
+						for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+							out.push_str(&format!("{}\t{}\n", indent, line));
+						}
+					}
+					_ => {}
+				}
+			}
+		}
+	}
+
+	out.push_str(&format!("{}</{}>\n", indent, name));
+}
+
+// Run 'cargo insta review' (or 'cargo insta test --accept') to accept changes to these.
+// Mismatches are collected rather than failing on the first one, so that a single run reports every page which changed.
+
+#[test]
+fn snapshots() {
+	let client = client();
+	let mut mismatches = Vec::new();
+
+	// '/projects' and '/articles' are the same page as '/' but with another tab checked, which is all 'tabs' needs to check.
+
+	for route in pages()
+		.into_iter()
+		.filter(|route| !["/projects", "/articles"].contains(&route.as_str()))
+	{
+		let document = page(&client, &route);
+		let name = match route.as_str() {
+			"/" => "index".to_string(),
+			_ => route.trim_start_matches('/').replace('/', "-"),
+		};
+
+		let mut html = String::new();
+		pretty(document.root_element(), 0, &mut html);
+
+		if panic::catch_unwind(|| insta::assert_snapshot!(name, html)).is_err() {
+			mismatches.push(route);
+		}
+	}
+
+	assert!(
+		mismatches.is_empty(),
+		"snapshots don't match: {}",
+		mismatches.join(", ")
+	);
+}